@@ -3,7 +3,8 @@ use crate::opaque::{load_assets, simple_asset_mod_file};
 
 const SOUND_DESTROY_DIR: &str =  "sound/effects/destroy/";
 const SOUND_EXPLOSION_DIR: &str =  "sound/effects/explosion/";
-const SOUND_COLLISION_DIR: &str =  "sound/effects/collision/";
+const SOUND_HARD_COLLISION_DIR: &str =  "sound/effects/collision/hard/";
+const SOUND_SOFT_COLLISION_DIR: &str =  "sound/effects/collision/soft/";
 const MUSIC_DIR: &str =  "sound/music/";
 
 pub fn build_sound_modules<P : AsRef<Path>>(root_dir: P) -> Result<(), String> {
@@ -12,7 +13,8 @@ pub fn build_sound_modules<P : AsRef<Path>>(root_dir: P) -> Result<(), String> {
         simple_asset_mod_file(dir.join("mod.rs"), assets)
     }
 
-    load_sound_assets(root_dir.as_ref().join(SOUND_COLLISION_DIR))?;
+    load_sound_assets(root_dir.as_ref().join(SOUND_HARD_COLLISION_DIR))?;
+    load_sound_assets(root_dir.as_ref().join(SOUND_SOFT_COLLISION_DIR))?;
     load_sound_assets(root_dir.as_ref().join(SOUND_DESTROY_DIR))?;
     load_sound_assets(root_dir.as_ref().join(SOUND_EXPLOSION_DIR))?;
     load_sound_assets(root_dir.as_ref().join(MUSIC_DIR))
@@ -8,7 +8,7 @@ use itertools::Itertools;
 use rayon::prelude::*;
 use texture_packer::{TexturePacker, TexturePackerConfig};
 use texture_packer::texture::Texture;
-use crate::geometry::{SpriteAsset, SpriteAssetSheet, SpriteSnip, SpriteTriangle};
+use crate::geometry::{Material, SpriteAsset, SpriteAssetSheet, SpriteSnip, SpriteTriangle};
 use crate::sprite::{ContourSpriteSnip, SpriteImage};
 use crate::triangulate::triangulate;
 
@@ -16,6 +16,7 @@ const LETTERS_SRC: &str = "letters";
 const NUMBERS_SRC: &str = "numbers";
 const META_FILE: &str =  "sprites.json.zst";
 const PNG_FILE: &str =  "sprites.png";
+const MATERIALS_FILE: &str =  "materials.json";
 const MAX_DIMENSION: u32 = 500;
 
 pub fn build_sprite_sheets<P : AsRef<Path>>(root_path: P) -> Result<(), String> {
@@ -32,13 +33,15 @@ fn build_sprite_sheet(asset_path: &Path, sprite_type: SpriteType) -> Result<(),
     let meta_path = asset_path.join(META_FILE);
     let packed_sprites_path = asset_path.join(PNG_FILE);
 
-    if meta_path.exists() && packed_sprites_path.exists() {
+    if meta_path.exists() && packed_sprites_path.exists() && !is_modified_since(&asset_path.join(MATERIALS_FILE), &meta_path) {
         return Ok(());
     }
 
+    let materials = load_materials(asset_path)?;
+
     // pack the sprites in sync
     let (packed_sprites, meta) = pack_sprites(
-        async_load_all_sprites(asset_path, sprite_type)
+        async_load_all_sprites(asset_path, sprite_type, &materials)
     )?;
 
     // save the sprite sheet
@@ -54,6 +57,24 @@ fn build_sprite_sheet(asset_path: &Path, sprite_type: SpriteType) -> Result<(),
     Ok(())
 }
 
+/// materials are assigned per source image e.g. { "00": "Rubber" } assigns rubber to all glyphs in 00.png
+fn load_materials(asset_path: &Path) -> Result<HashMap<String, Material>, String> {
+    let materials_path = asset_path.join(MATERIALS_FILE);
+    if !materials_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let file = File::open(materials_path).map_err(|e| e.to_string())?;
+    serde_json::from_reader(file).map_err(|e| e.to_string())
+}
+
+fn is_modified_since(path: &Path, other: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(path), modified(other)) {
+        (Some(modified), Some(other_modified)) => modified > other_modified,
+        _ => false
+    }
+}
+
 #[cfg(not(feature = "compress_sprites"))]
 fn save_png(src: RgbaImage, path: PathBuf) -> Result<(), String> {
     let mut file = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
@@ -117,7 +138,7 @@ impl SpriteType {
     }
 }
 
-fn async_load_all_sprites<P : AsRef<Path>>(path: P, sprite_type: SpriteType) -> Vec<PartialAsset> {
+fn async_load_all_sprites<P : AsRef<Path>>(path: P, sprite_type: SpriteType, materials: &HashMap<String, Material>) -> Vec<PartialAsset> {
     fs::read_dir(path)
         .expect("cannot read font source path")
         .into_iter()
@@ -128,7 +149,8 @@ fn async_load_all_sprites<P : AsRef<Path>>(path: P, sprite_type: SpriteType) ->
         .collect::<Vec<PathBuf>>()
         .into_par_iter()
         .map(|path| SpriteImage::new(path).expect("cannot read image"))
-        .flat_map(|image|
+        .flat_map(|image| {
+            let material = materials.get(image.name()).copied().unwrap_or_default();
             extract_chars(&image, sprite_type).expect("cannot get characters").into_iter().map(move |(character, snip)| {
                 let name = format!("{}/{}/{}", sprite_type.src_path(), image.name(), character);
                 let triangles = triangulate(snip.contour(), &image).expect("cannot triangulate");
@@ -136,9 +158,9 @@ fn async_load_all_sprites<P : AsRef<Path>>(path: P, sprite_type: SpriteType) ->
                 let scale = MAX_DIMENSION as f64 / snip.width().max(snip.height()) as f64;
                 let scaled = image.crop_and_scale(&snip, scale);
 
-                PartialAsset { name, sprite_image: scaled, character, triangles }
+                PartialAsset { name, sprite_image: scaled, character, triangles, material }
             }).collect::<Vec<PartialAsset>>()
-        ).collect()
+        }).collect()
 }
 
 fn pack_sprites(partial_assets: Vec<PartialAsset>) -> Result<(RgbaImage, SpriteAssetSheet), String> {
@@ -176,7 +198,8 @@ fn pack_sprites(partial_assets: Vec<PartialAsset>) -> Result<(RgbaImage, SpriteA
                 asset.name,
                 asset.character,
                 snip,
-                asset.triangles
+                asset.triangles,
+                asset.material
             )
         ).collect()
     );
@@ -256,7 +279,8 @@ struct PartialAsset {
     name: String,
     sprite_image: RgbaImage,
     character: char,
-    triangles: Vec<SpriteTriangle>
+    triangles: Vec<SpriteTriangle>,
+    material: Material
 }
 
 
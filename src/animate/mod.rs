@@ -1,26 +1,81 @@
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
 use self::event::AnimationEvent;
-use self::nuke::NukeAnimation;
+use self::nuke::NukePattern;
+use self::timeline::{Timeline, TimelinePlayer};
+use self::tween::{Easing, Look, Property, Tween};
 
-mod nuke;
+pub mod nuke;
 pub mod event;
+pub mod timeline;
+pub mod tween;
+
+const SPAWN_SECONDS: f64 = 0.25;
+const FLASH_SECONDS: f64 = 0.4;
 
 pub struct Animations {
-    nuke: NukeAnimation
+    nuke_pattern: NukePattern,
+    timelines: Vec<TimelinePlayer>,
+    looks: HashMap<u128, Look>,
+    /// bodies with a timeline that will destroy them
+    doomed: HashSet<u128>
 }
 
 impl Animations {
-    pub fn new() -> Self {
-        Self { nuke: NukeAnimation::new() }
+    pub fn new(nuke_pattern: NukePattern) -> Self {
+        Self { nuke_pattern, timelines: vec![], looks: HashMap::new(), doomed: HashSet::new() }
     }
 
     pub fn update(&mut self, delta: Duration) -> Vec<AnimationEvent> {
         let mut events = vec![];
-        self.nuke.update(delta, |e| events.push(e));
+        for timeline in self.timelines.iter_mut() {
+            timeline.update(delta, &mut self.looks, |e| events.push(e));
+        }
+        self.timelines.retain(|timeline| !timeline.is_finished());
+        for event in events.iter() {
+            match event {
+                AnimationEvent::DestroyAsset { id } => {
+                    self.doomed.remove(id);
+                    self.looks.remove(id);
+                }
+            }
+        }
+        self.looks.retain(|_, look| !look.is_unchanged());
         events
     }
 
-    pub fn nuke(&mut self, ids: Vec<u128>) {
-        self.nuke.nuke(ids);
+    pub fn play(&mut self, timeline: Timeline) {
+        self.timelines.push(TimelinePlayer::new(timeline));
+    }
+
+    /// how a body should be drawn differently right now
+    pub fn look(&self, id: u128) -> Look {
+        self.looks.get(&id).copied().unwrap_or_default()
+    }
+
+    /// destroys the bodies centered on points, ignoring any already on their way out
+    pub fn nuke(&mut self, bodies: Vec<(u128, Point)>, center: Point) {
+        let bodies = bodies.into_iter()
+            .filter(|(id, _)| self.doomed.insert(*id))
+            .collect::<Vec<_>>();
+        if !bodies.is_empty() {
+            self.play(self.nuke_pattern.timeline(bodies, center));
+        }
     }
-}
\ No newline at end of file
+
+    /// grows a new body from nothing
+    pub fn spawned(&mut self, id: u128) {
+        let duration = Duration::from_secs_f64(SPAWN_SECONDS);
+        // hidden until the timeline's first update
+        self.looks.entry(id).or_default().scale = 0.0;
+        self.play(Timeline::Tween(Tween::new(id, Property::Scale, 0.0, 1.0, duration).with_easing(Easing::EaseOut)));
+    }
+
+    /// briefly tints a body
+    pub fn flash(&mut self, id: u128, color: Color) {
+        let duration = Duration::from_secs_f64(FLASH_SECONDS);
+        self.play(Timeline::Tween(Tween::new(id, Property::Tint(color), 1.0, 0.0, duration).with_easing(Easing::EaseInOut)));
+    }
+}
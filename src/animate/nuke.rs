@@ -1,72 +1,80 @@
+use std::f32::consts::PI;
 use std::time::Duration;
+use sdl2::rect::Point;
+use serde::{Deserialize, Serialize};
 use super::event::AnimationEvent;
+use super::timeline::Timeline;
+use super::tween::{Easing, Property, Tween};
 
 const NUKE_DELAY: f64 = 0.1;
+const FADE_SECONDS: f64 = 0.25;
+/// times a spiral goes round to reach the furthest body
+const SPIRAL_TURNS: f32 = 3.0;
 
-#[derive(Debug, Clone)]
-pub struct NukeAnimationState {
-    delay: Duration,
-    duration: Duration,
-    queue: Vec<u128>
+/// The order the nuke key destroys bodies in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NukePattern {
+    /// one body after another
+    Sequential,
+    /// left to right across the screen
+    Sweep,
+    /// spiralling out from the center of the screen
+    Spiral
 }
 
-impl NukeAnimationState {
-    pub fn new(queue: Vec<u128>) -> Self {
-        let delay = Duration::from_secs_f64(NUKE_DELAY);
-        Self {
-            delay,
-            duration: delay, // destroy first body right away
-            queue
-        }
-    }
-
-    fn update<F : FnMut(u128)>(&mut self, delta: Duration, mut callback: F) {
-        if self.is_finished() {
-            return;
-        }
-        self.duration += delta;
-        while self.duration >= self.delay {
-            self.duration -= self.delay;
-            if let Some(id) = self.queue.pop() {
-                callback(id)
-            } else {
-                return;
+impl NukePattern {
+    /// ids of the bodies centered on points in the order they're destroyed
+    fn order(&self, mut bodies: Vec<(u128, Point)>, center: Point) -> Vec<u128> {
+        match self {
+            NukePattern::Sequential => bodies.reverse(),
+            NukePattern::Sweep => bodies.sort_by_key(|(_, point)| point.x()),
+            NukePattern::Spiral => {
+                let offset = |point: Point| ((point.x() - center.x()) as f32, (point.y() - center.y()) as f32);
+                let furthest = bodies.iter()
+                    .map(|(_, point)| { let (x, y) = offset(*point); x.hypot(y) })
+                    .fold(0.0, f32::max)
+                    .max(1.0);
+                // turns round the center as the distance grows
+                let turns = |point: Point| {
+                    let (x, y) = offset(point);
+                    SPIRAL_TURNS * x.hypot(y) / furthest + (y.atan2(x) + PI) / (2.0 * PI)
+                };
+                bodies.sort_by(|(_, a), (_, b)| turns(*a).total_cmp(&turns(*b)));
             }
         }
+        bodies.into_iter().map(|(id, _)| id).collect()
     }
 
-    fn is_finished(&self) -> bool {
-        self.queue.is_empty()
+    /// fades out & shrinks each body in turn before destroying it
+    pub fn timeline(&self, bodies: Vec<(u128, Point)>, center: Point) -> Timeline {
+        let delay = Duration::from_secs_f64(NUKE_DELAY);
+        let fade = Duration::from_secs_f64(FADE_SECONDS);
+        Timeline::Parallel(
+            self.order(bodies, center).into_iter()
+                .enumerate()
+                .map(|(index, id)| Timeline::Sequence(vec![
+                    Timeline::Wait(delay * index as u32),
+                    Timeline::Parallel(vec![
+                        Timeline::Tween(Tween::new(id, Property::Alpha, 1.0, 0.0, fade).with_easing(Easing::EaseIn)),
+                        Timeline::Tween(Tween::new(id, Property::Scale, 1.0, 0.5, fade).with_easing(Easing::EaseIn))
+                    ]),
+                    Timeline::Command(AnimationEvent::DestroyAsset { id })
+                ]))
+                .collect()
+        )
     }
-
-}
-
-pub struct NukeAnimation {
-    state: Option<NukeAnimationState>
 }
 
-impl NukeAnimation {
-    pub fn new() -> Self {
-        Self { state: None }
-    }
-
-    pub fn nuke(&mut self, ids: Vec<u128>) {
-        if let Some(state) = self.state.as_mut() {
-            // merge
-            state.queue = state.queue.iter().copied().chain(ids).collect::<Vec<u128>>();
-            state.queue.sort();
-            state.queue.dedup();
-        } else {
-            self.state = Some(NukeAnimationState::new(ids));
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    pub fn update<F : FnMut(AnimationEvent)>(&mut self, delta: Duration, mut callback: F) {
-        if let Some(state) = self.state.as_mut() {
-            state.update(delta, |id| callback(AnimationEvent::DestroyAsset { id }));
-            if state.is_finished() {
-                self.state = None;
-            }
-        }
+    #[test]
+    fn sweep_goes_left_to_right_and_spiral_starts_in_the_middle() {
+        let center = Point::new(50, 50);
+        let bodies = vec![(1, Point::new(90, 50)), (2, Point::new(10, 10)), (3, Point::new(52, 48))];
+        assert_eq!(NukePattern::Sequential.order(bodies.clone(), center), vec![3, 2, 1]);
+        assert_eq!(NukePattern::Sweep.order(bodies.clone(), center), vec![2, 3, 1]);
+        assert_eq!(NukePattern::Spiral.order(bodies, center)[0], 3);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use super::event::AnimationEvent;
+use super::tween::{Look, Tween};
+
+/// Game commands & tweens arranged in time
+#[derive(Debug, Clone)]
+pub enum Timeline {
+    /// sends a command to the game
+    Command(AnimationEvent),
+    /// eases a property of a body
+    Tween(Tween),
+    /// does nothing for a while
+    Wait(Duration),
+    /// one after another
+    Sequence(Vec<Timeline>),
+    /// all at once, lasting as long as the longest
+    Parallel(Vec<Timeline>)
+}
+
+impl Timeline {
+    pub fn duration(&self) -> Duration {
+        match self {
+            Timeline::Command(_) => Duration::ZERO,
+            Timeline::Tween(tween) => tween.duration,
+            Timeline::Wait(duration) => *duration,
+            Timeline::Sequence(timelines) => timelines.iter().map(|t| t.duration()).sum(),
+            Timeline::Parallel(timelines) => timelines.iter().map(|t| t.duration()).max().unwrap_or_default()
+        }
+    }
+
+    /// flattens into steps at the time they start
+    fn schedule(self, start: Duration, steps: &mut Vec<(Duration, Step)>) {
+        match self {
+            Timeline::Command(command) => steps.push((start, Step::Command(command))),
+            Timeline::Tween(tween) => steps.push((start, Step::Tween(tween))),
+            Timeline::Wait(_) => {}
+            Timeline::Sequence(timelines) => {
+                let mut at = start;
+                for timeline in timelines {
+                    let duration = timeline.duration();
+                    timeline.schedule(at, steps);
+                    at += duration;
+                }
+            }
+            Timeline::Parallel(timelines) => {
+                for timeline in timelines {
+                    timeline.schedule(start, steps);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Command(AnimationEvent),
+    Tween(Tween)
+}
+
+/// A timeline being played
+#[derive(Debug, Clone)]
+pub struct TimelinePlayer {
+    steps: Vec<(Duration, Step)>,
+    duration: Duration,
+    /// none until the first update so steps at the very start happen
+    elapsed: Option<Duration>
+}
+
+impl TimelinePlayer {
+    pub fn new(timeline: Timeline) -> Self {
+        let duration = timeline.duration();
+        let mut steps = vec![];
+        timeline.schedule(Duration::ZERO, &mut steps);
+        // stable so steps at the same time keep their order
+        steps.sort_by_key(|(at, _)| *at);
+        Self { steps, duration, elapsed: None }
+    }
+
+    /// sends commands that came due & sets the looks of bodies being tweened
+    pub fn update<F : FnMut(AnimationEvent)>(&mut self, delta: Duration, looks: &mut HashMap<u128, Look>, mut callback: F) {
+        let previous = self.elapsed;
+        let now = previous.unwrap_or_default() + delta;
+        self.elapsed = Some(now);
+        let after_previous = |at: Duration| previous.map_or(true, |previous| at > previous);
+        for (at, step) in self.steps.iter() {
+            if *at > now {
+                break;
+            }
+            match step {
+                Step::Command(command) => if after_previous(*at) {
+                    callback(*command);
+                }
+                // still running or finished since the last update
+                Step::Tween(tween) => if after_previous(*at + tween.duration) {
+                    let progress = if tween.duration.is_zero() {
+                        1.0
+                    } else {
+                        (now - *at).as_secs_f32() / tween.duration.as_secs_f32()
+                    };
+                    tween.apply(progress.min(1.0), looks.entry(tween.id).or_default());
+                }
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed.map_or(false, |elapsed| elapsed >= self.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animate::tween::Property;
+
+    fn millis(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn destroy(id: u128) -> Timeline {
+        Timeline::Command(AnimationEvent::DestroyAsset { id })
+    }
+
+    fn play(player: &mut TimelinePlayer, delta: Duration, looks: &mut HashMap<u128, Look>) -> Vec<u128> {
+        let mut ids = vec![];
+        player.update(delta, looks, |event| match event {
+            AnimationEvent::DestroyAsset { id } => ids.push(id)
+        });
+        ids
+    }
+
+    #[test]
+    fn sequences_follow_on_and_parallels_last_as_long_as_the_longest() {
+        let timeline = Timeline::Sequence(vec![
+            destroy(1),
+            Timeline::Parallel(vec![Timeline::Wait(millis(100)), Timeline::Wait(millis(300))]),
+            destroy(2),
+            Timeline::Wait(millis(100)),
+            destroy(3)
+        ]);
+        assert_eq!(timeline.duration(), millis(400));
+
+        let mut looks = HashMap::new();
+        let mut player = TimelinePlayer::new(timeline);
+        assert_eq!(play(&mut player, millis(16), &mut looks), vec![1]);
+        assert_eq!(play(&mut player, millis(284), &mut looks), vec![2]);
+        assert!(!player.is_finished());
+        // a long frame still sends everything it skipped over
+        assert_eq!(play(&mut player, millis(500), &mut looks), vec![3]);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn tweens_ease_looks_and_finish_on_their_last_value() {
+        let fade = Tween::new(1, Property::Alpha, 1.0, 0.0, millis(200));
+        let mut looks = HashMap::new();
+        let mut player = TimelinePlayer::new(Timeline::Sequence(vec![Timeline::Tween(fade), destroy(1)]));
+        assert!(play(&mut player, millis(100), &mut looks).is_empty());
+        assert_eq!(looks[&1].alpha, 0.5);
+        assert_eq!(play(&mut player, millis(200), &mut looks), vec![1]);
+        assert_eq!(looks[&1].alpha, 0.0);
+    }
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+/// How a tween gets from start to end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// slow start
+    EaseIn,
+    /// slow finish
+    EaseOut,
+    /// slow start & finish
+    EaseInOut
+}
+
+impl Easing {
+    /// eased progress for a linear progress between 0 & 1
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => if t < 0.5 { 2.0 * t * t } else { -1.0 + (4.0 - 2.0 * t) * t }
+        }
+    }
+}
+
+/// A property of how a body is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Property {
+    /// size relative to the body, 1 is actual size
+    Scale,
+    /// 0 is transparent, 1 is opaque
+    Alpha,
+    /// how strongly the body is tinted the color, 0 is not at all
+    Tint(Color)
+}
+
+/// Eases a property of a body from one value to another
+#[derive(Debug, Clone, Copy)]
+pub struct Tween {
+    pub id: u128,
+    pub property: Property,
+    pub from: f32,
+    pub to: f32,
+    pub duration: Duration,
+    pub easing: Easing
+}
+
+impl Tween {
+    pub fn new(id: u128, property: Property, from: f32, to: f32, duration: Duration) -> Self {
+        Self { id, property, from, to, duration, easing: Easing::Linear }
+    }
+
+    pub fn with_easing(self, easing: Easing) -> Self {
+        Self { easing, ..self }
+    }
+
+    /// sets the property on the look at a linear progress between 0 & 1
+    pub fn apply(&self, progress: f32, look: &mut Look) {
+        let value = self.from + (self.to - self.from) * self.easing.apply(progress);
+        match self.property {
+            Property::Scale => look.scale = value.max(0.0),
+            Property::Alpha => look.alpha = value.clamp(0.0, 1.0),
+            Property::Tint(color) => look.tint = Some((color, value.clamp(0.0, 1.0)))
+        }
+    }
+}
+
+/// Changes to how a body is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Look {
+    pub scale: f32,
+    pub alpha: f32,
+    pub tint: Option<(Color, f32)>
+}
+
+impl Default for Look {
+    fn default() -> Self {
+        Self { scale: 1.0, alpha: 1.0, tint: None }
+    }
+}
+
+impl Look {
+    /// drawn the same as without a look
+    pub fn is_unchanged(&self) -> bool {
+        self.scale == 1.0 && self.alpha == 1.0 && self.tint.map_or(true, |(_, strength)| strength <= 0.0)
+    }
+
+    /// aabb scaled about its center
+    pub fn scale_rect(&self, aabb: Rect) -> Rect {
+        if self.scale == 1.0 {
+            return aabb;
+        }
+        let width = (aabb.width() as f32 * self.scale).round().max(1.0) as u32;
+        let height = (aabb.height() as f32 * self.scale).round().max(1.0) as u32;
+        Rect::from_center(aabb.center(), width, height)
+    }
+
+    pub fn alpha_mod(&self) -> u8 {
+        (255.0 * self.alpha).round() as u8
+    }
+
+    /// texture color mod, white towards the tint color
+    pub fn color_mod(&self) -> (u8, u8, u8) {
+        match self.tint {
+            Some((color, strength)) => {
+                let channel = |c: u8| (255.0 - (255.0 - c as f32) * strength).round() as u8;
+                (channel(color.r), channel(color.g), channel(color.b))
+            }
+            None => (255, 255, 255)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn tween_sets_look() {
+        let mut look = Look::default();
+        let fade = Tween::new(1, Property::Alpha, 1.0, 0.0, Duration::from_secs(1));
+        fade.apply(0.25, &mut look);
+        assert_eq!(look.alpha, 0.75);
+        assert!(!look.is_unchanged());
+        fade.apply(0.0, &mut look);
+        assert!(look.is_unchanged());
+
+        let flash = Tween::new(1, Property::Tint(Color::RGB(255, 0, 0)), 1.0, 0.0, Duration::from_secs(1));
+        flash.apply(0.0, &mut look);
+        assert_eq!(look.color_mod(), (255, 0, 0));
+        flash.apply(1.0, &mut look);
+        assert!(look.is_unchanged());
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::Formatter;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
+use sdl2::rect::Point;
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct SpritePoint {
@@ -19,6 +20,12 @@ impl SpritePoint {
     }
 }
 
+impl From<Point> for SpritePoint {
+    fn from(point: Point) -> Self {
+        Self::new(point.x() as f64, point.y() as f64)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SpriteTriangle {
     points: [SpritePoint; 3],
@@ -134,19 +141,46 @@ impl SpriteSnip {
     }
 }
 
+/// The physical material of a sprite, see game::material for the physical properties of each
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Material {
+    #[default]
+    Default,
+    Rubber,
+    Wood,
+    Metal,
+    Jelly,
+    Balloon
+}
+
+/// Index of a loaded sprite, cheaper to pass around than its name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SpriteId(pub u32);
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SpriteAsset {
+    #[serde(skip)]
+    id: SpriteId,
     name: String,
     character: char,
     snip: SpriteSnip,
-    triangles: Vec<SpriteTriangle>
+    triangles: Vec<SpriteTriangle>,
+    #[serde(default)]
+    material: Material
 }
 
 impl SpriteAsset {
-    pub fn new(name: String, character: char, snip: SpriteSnip, triangles: Vec<SpriteTriangle>) -> Self {
-        Self { name, character, snip, triangles }
+    pub fn new(name: String, character: char, snip: SpriteSnip, triangles: Vec<SpriteTriangle>, material: Material) -> Self {
+        Self { id: SpriteId::default(), name, character, snip, triangles, material }
+    }
+
+    pub fn with_id(self, id: SpriteId) -> Self {
+        Self { id, ..self }
     }
 
+    pub fn id(&self) -> SpriteId {
+        self.id
+    }
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -159,6 +193,9 @@ impl SpriteAsset {
     pub fn triangles(&self) -> &Vec<SpriteTriangle> {
         &self.triangles
     }
+    pub fn material(&self) -> Material {
+        self.material
+    }
 
     pub fn unit_scale(&self) -> (f64, f64) {
         let unit_scale = 1.0 / self.snip.width.max(self.snip.height) as f64;
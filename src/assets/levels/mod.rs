@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use crate::assets::geometry::Material;
+
+/// Levels bundled with the game & loaded from level files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum LevelName {
+    #[default]
+    Playground,
+    Workshop,
+    /// index of a level loaded from a level file, see `register`
+    #[strum(disabled)]
+    Custom(u16)
+}
+
+/// levels loaded from level files by name
+static CUSTOM: OnceLock<Vec<(String, Level)>> = OnceLock::new();
+
+/// adds levels loaded from level files, once before the game starts
+pub fn register(levels: Vec<(String, Level)>) -> Result<(), String> {
+    let mut custom: Vec<(String, Level)> = vec![];
+    for (name, level) in levels {
+        if LevelName::by_name(&name).is_some() || custom.iter().any(|(n, _)| *n == name) {
+            println!("Level {} is defined more than once, skipping", name);
+            continue;
+        }
+        custom.push((name, level));
+    }
+    CUSTOM.set(custom).map_err(|_| "levels are already registered".to_string())
+}
+
+/// every level in the directory named after its file, nothing if there is no directory, bad levels are skipped
+pub fn load_levels(path: &Path) -> Result<Vec<(String, Level)>, String> {
+    if !path.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut result = vec![];
+    for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
+        let file = entry.map_err(|e| e.to_string())?.path();
+        if file.extension().map_or(true, |extension| extension != "json") {
+            continue;
+        }
+        let name = match file.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue
+        };
+        match fs::read_to_string(&file).map_err(|e| e.to_string()).and_then(|json| Level::parse(&json)) {
+            Ok(level) => result.push((name, level)),
+            Err(error) => println!("Bad level at {}, {}, skipping", file.display(), error)
+        }
+    }
+    Ok(result)
+}
+
+impl LevelName {
+    /// bundled levels then levels from files
+    fn all() -> impl Iterator<Item = LevelName> {
+        let custom = CUSTOM.get().map_or(0, |levels| levels.len());
+        Self::iter().chain((0..custom).map(|index| LevelName::Custom(index as u16)))
+    }
+
+    fn by_name(name: &str) -> Option<LevelName> {
+        Self::all().find(|level| level.name() == name)
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            LevelName::Custom(index) => CUSTOM.get()
+                .and_then(|levels| levels.get(*index as usize))
+                .map_or_else(|| format!("Custom{}", index), |(name, _)| name.clone()),
+            level => format!("{:?}", level)
+        }
+    }
+
+    pub fn load(&self) -> Result<Level, String> {
+        match self {
+            LevelName::Playground => Level::parse(include_str!("playground.json")),
+            LevelName::Workshop => Level::parse(include_str!("workshop.json")),
+            LevelName::Custom(index) => CUSTOM.get()
+                .and_then(|levels| levels.get(*index as usize))
+                .map(|(_, level)| level.clone())
+                .ok_or_else(|| format!("level {} is not registered", index))
+        }
+    }
+
+    pub fn into_next(self) -> Self {
+        let levels = Self::all().collect::<Vec<LevelName>>();
+        let index = levels.iter().position(|level| *level == self).map_or(0, |index| index + 1);
+        levels[index % levels.len()]
+    }
+}
+
+/// levels are saved by name so level files can be added & removed
+impl Serialize for LevelName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for LevelName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::by_name(&name).ok_or_else(|| D::Error::custom(format!("unknown level {}", name)))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Level {
+    props: Vec<Prop>
+}
+
+impl Level {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let level: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for prop in level.props.iter() {
+            prop.validate()?;
+        }
+        Ok(level)
+    }
+
+    pub fn props(&self) -> &Vec<Prop> {
+        &self.props
+    }
+}
+
+/// How a prop moves
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum PropMotion {
+    /// never moves
+    Static,
+    /// spins at a constant angular velocity in degrees per second
+    Kinematic { angular_velocity: f32 },
+    /// swings freely around its position like a seesaw
+    Pivot
+}
+
+/// A piece of scenery in a level
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Prop {
+    motion: PropMotion,
+    /// center of the prop as a fraction of the world width & height
+    position: (f32, f32),
+    /// degrees
+    #[serde(default)]
+    angle: f32,
+    /// convex polygons relative to the position, as a fraction of the world height so props keep their shape
+    shapes: Vec<Vec<(f32, f32)>>,
+    #[serde(default)]
+    material: Material,
+    color: (u8, u8, u8)
+}
+
+impl Prop {
+    fn validate(&self) -> Result<(), String> {
+        let (x, y) = self.position;
+        let points = self.shapes.iter().flatten().copied();
+        if ![(x, y)].into_iter().chain(points).all(|(x, y)| x.is_finite() && y.is_finite()) || !self.angle.is_finite() {
+            return Err(format!("prop at {:?} has a position, angle or point that is not a number", self.position));
+        }
+        if self.shapes.iter().any(|shape| shape.len() < 3) {
+            return Err(format!("prop at {:?} has a shape with fewer than 3 points", self.position));
+        }
+        Ok(())
+    }
+
+    pub fn motion(&self) -> PropMotion {
+        self.motion
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    pub fn material(&self) -> Material {
+        self.material
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        self.color
+    }
+
+    /// the convex shapes split into triangles
+    pub fn triangles(&self) -> Vec<[(f32, f32); 3]> {
+        self.shapes.iter()
+            .filter(|shape| shape.len() >= 3)
+            .flat_map(|shape| (1..shape.len() - 1).map(|i| [shape[0], shape[i], shape[i + 1]]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_levels_load() {
+        for name in LevelName::iter() {
+            let level = name.load().unwrap();
+            assert!(!level.props().is_empty(), "{:?} has no props", name);
+        }
+    }
+
+    #[test]
+    fn levels_rotate_through_every_level() {
+        assert_eq!(LevelName::Playground.into_next(), LevelName::Workshop);
+        assert_eq!(LevelName::Workshop.into_next(), LevelName::Playground);
+    }
+
+    #[test]
+    fn levels_are_saved_by_name_and_bad_levels_do_not_parse() {
+        let json = serde_json::to_string(&LevelName::Workshop).unwrap();
+        assert_eq!(json, "\"Workshop\"");
+        assert_eq!(serde_json::from_str::<LevelName>(&json).unwrap(), LevelName::Workshop);
+        assert!(serde_json::from_str::<LevelName>("\"Nowhere\"").is_err());
+
+        assert!(Level::parse("{ \"props\": [").is_err());
+        let line = r#"{ "props": [{ "motion": "Static", "position": [0.5, 0.5], "shapes": [[[0, 0], [1, 0]]], "color": [0, 0, 0] }] }"#;
+        assert!(Level::parse(line).is_err());
+        assert!(LevelName::Custom(u16::MAX).load().is_err());
+    }
+}
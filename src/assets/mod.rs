@@ -1,5 +1,6 @@
 pub mod geometry;
 pub mod sprites;
 pub mod sound;
+pub mod levels;
 mod letters;
 mod numbers;
\ No newline at end of file
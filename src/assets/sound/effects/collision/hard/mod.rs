@@ -0,0 +1,7 @@
+pub const ASSETS: [&[u8]; 5] = [
+    include_bytes!("42905__freqman__glass-piece.ogg"),
+    include_bytes!("43601__freqman__object-falls-3.ogg"),
+    include_bytes!("43602__freqman__object-falls-4.ogg"),
+    include_bytes!("43603__freqman__object-falls-5.ogg"),
+    include_bytes!("43604__freqman__object-falls.ogg"),
+];
\ No newline at end of file
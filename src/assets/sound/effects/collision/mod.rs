@@ -1,10 +1,2 @@
-pub const ASSETS: [&[u8]; 8] = [
-    include_bytes!("42905__freqman__glass-piece.ogg"),
-    include_bytes!("43601__freqman__object-falls-3.ogg"),
-    include_bytes!("43602__freqman__object-falls-4.ogg"),
-    include_bytes!("43603__freqman__object-falls-5.ogg"),
-    include_bytes!("43604__freqman__object-falls.ogg"),
-    include_bytes!("43605__freqman__sandbag-2.ogg"),
-    include_bytes!("43606__freqman__sandbag-3.ogg"),
-    include_bytes!("43607__freqman__sandbag.ogg"),
-];
\ No newline at end of file
+pub mod hard;
+pub mod soft;
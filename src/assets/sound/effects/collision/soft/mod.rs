@@ -0,0 +1,5 @@
+pub const ASSETS: [&[u8]; 3] = [
+    include_bytes!("43605__freqman__sandbag-2.ogg"),
+    include_bytes!("43606__freqman__sandbag-3.ogg"),
+    include_bytes!("43607__freqman__sandbag.ogg"),
+];
\ No newline at end of file
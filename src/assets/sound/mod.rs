@@ -4,11 +4,12 @@ use std::rc::Rc;
 use rand::{Rng, thread_rng};
 use rand::seq::SliceRandom;
 use sdl2::get_error;
-use sdl2::mixer::{Channel, Chunk, Music, reserve_channels};
+use sdl2::mixer::{Channel, Chunk, MAX_VOLUME, Music, reserve_channels};
 use sdl2::rwops::RWops;
 use sdl2::sys::mixer;
 use crate::assets::sound::playable::Playable;
 use crate::config::AudioConfig;
+use crate::game::material::CollisionSound;
 use crate::random::BagRandom;
 
 pub mod playable;
@@ -23,7 +24,8 @@ pub struct Sound {
     alphanumeric: HashMap<char, Chunk>,
     destroy: BagRandom<Chunk>,
     explosion: BagRandom<Chunk>,
-    collision: BagRandom<Chunk>,
+    hard_collision: BagRandom<Chunk>,
+    soft_collision: BagRandom<Chunk>,
     collision_channels: [Channel; COLLISION_CHANNELS]
 }
 
@@ -40,7 +42,8 @@ impl Sound {
         let alphanumeric = effects::alphanumeric::alphanumeric_sounds(&config);
         let destroy = Self::load_sounds(&config, &effects::destroy::ASSETS);
         let explosion = Self::load_sounds(&config, &effects::explosion::ASSETS);
-        let collision = Self::load_sounds(&config, &effects::collision::ASSETS);
+        let hard_collision = Self::load_sounds(&config, &effects::collision::hard::ASSETS);
+        let soft_collision = Self::load_sounds(&config, &effects::collision::soft::ASSETS);
 
         reserve_channels(COLLISION_CHANNELS as i32);
         let collision_channels = (0..COLLISION_CHANNELS)
@@ -49,7 +52,7 @@ impl Sound {
             .try_into()
             .unwrap();
 
-        Ok(Self { alphanumeric, destroy, explosion, collision, collision_channels })
+        Ok(Self { alphanumeric, destroy, explosion, hard_collision, soft_collision, collision_channels })
     }
 
     pub fn play_alphanumeric(&self, ch: char) {
@@ -66,11 +69,23 @@ impl Sound {
         self.explosion.next().unwrap().try_play();
     }
 
-    pub fn play_collision(&mut self) {
-        for channel in self.collision_channels.iter() {
-            if !channel.is_playing() {
-                channel.play(self.collision.next().unwrap().as_ref(), 0).unwrap();
-            }
+    /// panning is 0 for the far left of the screen to 1 for the far right, volume is 0..1
+    pub fn play_collision(&mut self, collision_sound: CollisionSound, panning: f32, volume: f32) {
+        if let Some(channel) = self.collision_channels.iter().copied().find(|c| !c.is_playing()) {
+            let is_hard = match collision_sound {
+                CollisionSound::Mixed => thread_rng().gen_bool(0.5),
+                CollisionSound::Hard => true,
+                CollisionSound::Soft => false
+            };
+            let bank = if is_hard { &mut self.hard_collision } else { &mut self.soft_collision };
+
+            // centered sounds play at full volume in both speakers
+            let panning = panning.clamp(0.0, 1.0);
+            let left = (2.0 * (1.0 - panning) * 255.0).min(255.0) as u8;
+            let right = (2.0 * panning * 255.0).min(255.0) as u8;
+            let _ = channel.set_panning(left, right);
+            channel.set_volume((volume.clamp(0.0, 1.0) * MAX_VOLUME as f32).round() as i32);
+            channel.play(bank.next().unwrap().as_ref(), 0).unwrap();
         }
     }
 
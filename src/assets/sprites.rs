@@ -1,24 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 use itertools::Itertools;
 use std::default::Default;
 use sdl2::gfx::primitives::DrawRenderer;
-use sdl2::image::LoadTexture;
 use sdl2::rect::Rect;
 use sdl2::render::{Texture, TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
-use crate::assets::geometry::{SpriteAsset, SpriteAssetSheet};
-use crate::game::physics::Body;
+use crate::assets::geometry::{SpriteAsset, SpriteAssetSheet, SpriteId};
+use crate::game::physics::BodyType;
+use crate::game::snapshot::WorldSnapshot;
 use crate::assets::letters;
 use crate::assets::numbers;
 use crate::random::BagRandom;
+use crate::animate::tween::Look;
+use crate::texture::TextureFactory;
 
 pub struct Sprites<'a> {
     char_bag: BagRandom<char>,
-    sprites_by_name: HashMap<String, SpriteAsset>,
+    sprites_by_id: Vec<SpriteAsset>,
     sprites_by_char: HashMap<char, BagRandom<SpriteAsset>>,
     letters: Texture<'a>,
     numbers: Texture<'a>,
+    /// ids that were drawn without a sprite, reported once each
+    missing: HashSet<SpriteId>,
 }
 
 impl<'a> Sprites<'a> {
@@ -34,11 +38,11 @@ impl<'a> Sprites<'a> {
     pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> Result<Self, String> {
         let sprites = Self::load_sprites(letters::SPRITES_JSON_ZST)?.into_iter()
             .chain(Self::load_sprites(numbers::SPRITES_JSON_ZST)?.into_iter())
+            .enumerate()
+            .map(|(index, sprite)| sprite.with_id(SpriteId(index as u32)))
             .collect::<Vec<SpriteAsset>>();
 
-        let sprites_by_name: HashMap<String, SpriteAsset> = sprites.iter()
-            .map(|sprite| (sprite.name().to_string(), sprite.clone()))
-            .collect();
+        let sprites_by_id = sprites.clone();
 
         let mut sprites_by_char: HashMap<char, BagRandom<SpriteAsset>> = sprites.into_iter()
             .sorted_by(|a, b| a.character().cmp(&b.character()))
@@ -49,14 +53,15 @@ impl<'a> Sprites<'a> {
 
         let char_bag = BagRandom::new(sprites_by_char.keys().copied().collect());
 
-        let letters = texture_creator.load_texture_bytes(letters::SPRITES_PNG)?;
-        let numbers = texture_creator.load_texture_bytes(numbers::SPRITES_PNG)?;
+        // blended so they can be faded out
+        let letters = texture_creator.load_texture_bytes_blended(letters::SPRITES_PNG)?;
+        let numbers = texture_creator.load_texture_bytes_blended(numbers::SPRITES_PNG)?;
 
-        Ok(Self { sprites_by_name, sprites_by_char, letters, numbers, char_bag })
+        Ok(Self { sprites_by_id, sprites_by_char, letters, numbers, char_bag, missing: HashSet::new() })
     }
 
     pub fn names(&self) -> Vec<String> {
-        self.sprites_by_name.keys().into_iter().cloned().collect()
+        self.sprites_by_id.iter().map(|sprite| sprite.name().to_string()).collect()
     }
 
     pub fn pick_sprite_by_char(&mut self, key: char) -> Option<SpriteAsset> {
@@ -70,20 +75,56 @@ impl<'a> Sprites<'a> {
         self.pick_sprite_by_char(*ch).unwrap()
     }
 
-    pub fn draw_sprite(&self, canvas: &mut WindowCanvas, name: &str, aabb: Rect, angle: f64) -> Result<(), String> {
-        if let Some(sprite) = self.sprites_by_name.get(name) {
+    pub fn draw_sprite(&mut self, canvas: &mut WindowCanvas, id: SpriteId, aabb: Rect, angle: f64, look: Look) -> Result<(), String> {
+        if let Some(sprite) = self.sprites_by_id.get(id.0 as usize) {
             let snip = sprite.snip();
+            let texture = if sprite.character().is_numeric() { &mut self.numbers } else { &mut self.letters };
+            let (r, g, b) = look.color_mod();
+            texture.set_color_mod(r, g, b);
+            texture.set_alpha_mod(look.alpha_mod());
             canvas.copy_ex(
-                if sprite.character().is_numeric() { &self.numbers } else { &self.letters },
+                texture,
                 Rect::new(snip.x() as i32, snip.y() as i32, snip.width(), snip.height()),
-                aabb,
+                look.scale_rect(aabb),
                 angle,
                 None,
                 false,
                 false
             )
         } else {
-            Err(format!("unknown sprite {}", name))
+            // not worth ending the game over but likely a bad session restore
+            if self.missing.insert(id) {
+                println!("No sprite with id {}, not drawing it", id.0);
+            }
+            Ok(())
+        }
+    }
+
+    /// names the sprites of alphanumerics in a snapshot as ids change when the sprites do
+    pub fn name_sprites(&self, snapshot: &mut WorldSnapshot) {
+        for body in snapshot.bodies.iter_mut() {
+            if let BodyType::Alphanumeric(alphanumeric) = &body.body_type {
+                body.sprite = self.sprites_by_id.get(alphanumeric.sprite.0 as usize)
+                    .map(|sprite| sprite.name().to_string());
+            }
         }
     }
+
+    /// finds the ids of named sprites in a snapshot, dropping alphanumerics whose sprite no longer exists
+    pub fn find_sprites(&self, snapshot: &mut WorldSnapshot) {
+        snapshot.bodies.retain_mut(|body| match &mut body.body_type {
+            BodyType::Alphanumeric(alphanumeric) => {
+                let found = body.sprite.as_ref()
+                    .and_then(|name| self.sprites_by_id.iter().find(|sprite| sprite.name() == name));
+                match found {
+                    Some(sprite) => {
+                        alphanumeric.sprite = sprite.id();
+                        true
+                    }
+                    None => false
+                }
+            }
+            _ => true
+        });
+    }
 }
\ No newline at end of file
@@ -1,6 +1,22 @@
 use std::time::Duration;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug)]
+// names of the animation for each character state, see `CharacterState::animation`
+/// loops while the character is alive & in states without an animation of their own
+pub const WALK: &str = "walk";
+/// plays once as the character dies
+pub const DIE: &str = "die";
+/// plays once when the character is created
+pub const SPAWN: &str = "spawn";
+pub const IDLE: &str = "idle";
+pub const SLEEP: &str = "sleep";
+pub const EAT: &str = "eat";
+pub const HURT: &str = "hurt";
+
+/// a zero length frame would never move on
+const MIN_FRAME_DURATION: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SpriteAnimationType {
     Static,
     Linear {
@@ -17,6 +33,10 @@ pub enum SpriteAnimationType {
         pause_for: Duration,
         resume_from_frame: usize,
     },
+    /// each frame shows for its own duration, e.g. from a sprite atlas
+    Timed {
+        durations: Vec<Duration>,
+    },
 }
 
 impl SpriteAnimationType {
@@ -30,11 +50,12 @@ impl SpriteAnimationType {
             SpriteAnimationType::Linear { frames, .. } => *frames,
             SpriteAnimationType::YoYo { frames, .. } => *frames,
             SpriteAnimationType::LinearWithPause { frames, .. } => *frames,
+            SpriteAnimationType::Timed { durations } => durations.len(),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SpriteAnimation {
     animation_type: SpriteAnimationType,
     duration: Duration,
@@ -47,6 +68,7 @@ pub struct SpriteAnimation {
 
 impl SpriteAnimation {
     pub fn new(animation_type: SpriteAnimationType) -> Self {
+        let max_frame = animation_type.max_frame();
         Self {
             animation_type,
             duration: Duration::ZERO,
@@ -54,7 +76,7 @@ impl SpriteAnimation {
             frame: 0,
             invert: false,
             iteration: 0,
-            max_frame: animation_type.max_frame(),
+            max_frame,
         }
     }
 
@@ -104,11 +126,28 @@ impl SpriteAnimation {
                     }
                 }
             }
+            SpriteAnimationType::Timed { .. } => self.next_timed(),
+        }
+    }
+
+    fn next_timed(&mut self) {
+        if let SpriteAnimationType::Timed { durations } = &self.animation_type {
+            if durations.is_empty() {
+                return;
+            }
+            while let Some(remainder) = self.duration.checked_sub(durations[self.frame].max(MIN_FRAME_DURATION)) {
+                self.duration = remainder;
+                self.frame += 1;
+                if self.frame >= self.max_frame {
+                    self.iteration += 1;
+                    self.frame = 0;
+                }
+            }
         }
     }
 
     fn register_frames(&mut self, frame_duration: Duration) {
-        while let Some(remainder) = self.duration.checked_sub(frame_duration) {
+        while let Some(remainder) = self.duration.checked_sub(frame_duration.max(MIN_FRAME_DURATION)) {
             self.duration = remainder;
             self.frame += 1;
         }
@@ -131,6 +170,13 @@ impl SpriteAnimation {
         self.invert = false;
     }
 
+    /// from the start as if it had never played
+    pub fn restart(&mut self) {
+        self.reset();
+        self.paused_for = None;
+        self.iteration = 0;
+    }
+
     pub fn frame(&self) -> usize {
         if self.invert {
             self.max_frame - self.frame - 1
@@ -142,4 +188,35 @@ impl SpriteAnimation {
     pub fn iteration(&self) -> usize {
         self.iteration
     }
+
+    /// a single frame that never plays through
+    pub fn is_static(&self) -> bool {
+        matches!(self.animation_type, SpriteAnimationType::Static)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_frames_show_for_their_own_duration() {
+        let mut animation = SpriteAnimationType::Timed {
+            durations: vec![Duration::from_millis(100), Duration::from_millis(300)]
+        }.into_animation();
+        animation.update(Duration::from_millis(150));
+        assert_eq!(animation.frame(), 1);
+        animation.update(Duration::from_millis(200));
+        assert_eq!(animation.frame(), 1);
+        animation.update(Duration::from_millis(100));
+        assert_eq!(animation.frame(), 0);
+        assert_eq!(animation.iteration(), 1);
+    }
+
+    #[test]
+    fn zero_length_frames_still_move_on() {
+        let mut animation = SpriteAnimationType::Linear { frames: 2, duration: Duration::ZERO }.into_animation();
+        animation.update(Duration::from_millis(3));
+        assert_eq!(animation.iteration(), 1);
+    }
 }
@@ -0,0 +1,227 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::Deserialize;
+
+/// One frame of a sprite atlas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasFrame {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// how long the frame shows for, if the atlas says
+    pub duration: Option<Duration>
+}
+
+/// Frames at arbitrary positions on a sprite sheet as exported by aseprite or texture packer,
+/// either as a json array or a json hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteAtlas {
+    frames: Vec<AtlasFrame>,
+    /// frame indices of each named animation, in the order they play
+    animations: Vec<(String, Vec<usize>)>
+}
+
+#[derive(Deserialize)]
+struct AtlasJson {
+    frames: FramesJson,
+    #[serde(default)]
+    meta: MetaJson
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FramesJson {
+    Array(Vec<FrameJson>),
+    Hash(HashMap<String, FrameJson>)
+}
+
+#[derive(Deserialize)]
+struct FrameJson {
+    #[serde(default)]
+    filename: String,
+    frame: RectJson,
+    #[serde(default)]
+    rotated: bool,
+    /// milliseconds, only in aseprite exports
+    duration: Option<u64>
+}
+
+#[derive(Deserialize)]
+struct RectJson {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32
+}
+
+#[derive(Deserialize, Default)]
+struct MetaJson {
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<TagJson>
+}
+
+/// an aseprite tag, from & to are inclusive
+#[derive(Deserialize)]
+struct TagJson {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String
+}
+
+impl SpriteAtlas {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let atlas: AtlasJson = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let frames = match atlas.frames {
+            FramesJson::Array(frames) => frames,
+            FramesJson::Hash(frames) => {
+                let mut frames = frames.into_iter()
+                    .map(|(filename, frame)| FrameJson { filename, ..frame })
+                    .collect::<Vec<_>>();
+                // hashes have no order so put "walk 2" before "walk 10"
+                frames.sort_by(|a, b| natural_order(&a.filename, &b.filename));
+                frames
+            }
+        };
+        if frames.is_empty() {
+            return Err("atlas has no frames".to_string());
+        }
+        if let Some(frame) = frames.iter().find(|frame| frame.rotated) {
+            return Err(format!("rotated frames are not supported: {}", frame.filename));
+        }
+
+        let animations = if atlas.meta.frame_tags.is_empty() {
+            animations_by_filename(&frames)
+        } else {
+            let mut animations = vec![];
+            for tag in atlas.meta.frame_tags {
+                if tag.from > tag.to || tag.to >= frames.len() {
+                    return Err(format!("animation {} is outside the atlas", tag.name));
+                }
+                let forward = (tag.from..=tag.to).collect::<Vec<_>>();
+                let indices = match tag.direction.as_str() {
+                    "reverse" => forward.into_iter().rev().collect(),
+                    // back again without repeating either end
+                    "pingpong" => {
+                        let back = forward.iter().rev().skip(1).take(forward.len().saturating_sub(2)).copied().collect::<Vec<_>>();
+                        forward.into_iter().chain(back).collect()
+                    }
+                    _ => forward
+                };
+                animations.push((tag.name, indices));
+            }
+            animations
+        };
+
+        let frames = frames.into_iter()
+            .map(|frame| AtlasFrame {
+                x: frame.frame.x,
+                y: frame.frame.y,
+                width: frame.frame.w,
+                height: frame.frame.h,
+                duration: frame.duration.map(Duration::from_millis)
+            })
+            .collect();
+        Ok(Self { frames, animations })
+    }
+
+    /// every frame in the atlas
+    pub fn frames(&self) -> &[AtlasFrame] {
+        &self.frames
+    }
+
+    /// frames of the named animation in the order they play
+    pub fn animation(&self, name: &str) -> Option<Vec<AtlasFrame>> {
+        self.animations.iter()
+            .find(|(animation, _)| animation == name)
+            .map(|(_, indices)| indices.iter().map(|&i| self.frames[i]).collect())
+    }
+}
+
+/// without tags, frames named like "walk_0.png", "walk_1.png" make a "walk" animation
+fn animations_by_filename(frames: &[FrameJson]) -> Vec<(String, Vec<usize>)> {
+    let mut animations: Vec<(String, Vec<usize>)> = vec![];
+    for (index, frame) in frames.iter().enumerate() {
+        let name = animation_name(&frame.filename);
+        match animations.iter_mut().find(|(animation, _)| *animation == name) {
+            Some((_, indices)) => indices.push(index),
+            None => animations.push((name, vec![index]))
+        }
+    }
+    animations
+}
+
+fn animation_name(filename: &str) -> String {
+    let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+    stem.trim_end_matches(|c: char| c.is_ascii_digit())
+        .trim_end_matches([' ', '_', '-'])
+        .to_string()
+}
+
+/// by name then by the number at the end of the name
+fn natural_order(a: &str, b: &str) -> Ordering {
+    let number = |filename: &str| {
+        let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+        let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        stem[stem.len() - digits..].parse::<u64>().ok()
+    };
+    animation_name(a).cmp(&animation_name(b))
+        .then_with(|| number(a).cmp(&number(b)))
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aseprite_tags_are_animations() {
+        let atlas = SpriteAtlas::parse(r#"{
+            "frames": [
+                { "filename": "frog 0.aseprite", "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "rotated": false, "duration": 100 },
+                { "filename": "frog 1.aseprite", "frame": { "x": 16, "y": 0, "w": 16, "h": 16 }, "rotated": false, "duration": 200 },
+                { "filename": "frog 2.aseprite", "frame": { "x": 32, "y": 0, "w": 16, "h": 16 }, "rotated": false, "duration": 100 }
+            ],
+            "meta": { "frameTags": [
+                { "name": "walk", "from": 0, "to": 1, "direction": "forward" },
+                { "name": "eat", "from": 0, "to": 2, "direction": "pingpong" }
+            ] }
+        }"#).unwrap();
+        let walk = atlas.animation("walk").unwrap();
+        assert_eq!(walk.len(), 2);
+        assert_eq!(walk[1].x, 16);
+        assert_eq!(walk[1].duration, Some(Duration::from_millis(200)));
+        let eat = atlas.animation("eat").unwrap().iter().map(|frame| frame.x).collect::<Vec<_>>();
+        assert_eq!(eat, vec![0, 16, 32, 16]);
+        assert!(atlas.animation("hurt").is_none());
+    }
+
+    #[test]
+    fn texture_packer_hash_is_grouped_by_filename() {
+        let atlas = SpriteAtlas::parse(r#"{
+            "frames": {
+                "walk_10.png": { "frame": { "x": 20, "y": 0, "w": 10, "h": 10 } },
+                "walk_2.png": { "frame": { "x": 10, "y": 0, "w": 10, "h": 10 } },
+                "hurt.png": { "frame": { "x": 0, "y": 10, "w": 10, "h": 10 } },
+                "walk_1.png": { "frame": { "x": 0, "y": 0, "w": 10, "h": 10 } }
+            },
+            "meta": { "app": "https://www.codeandweb.com/texturepacker" }
+        }"#).unwrap();
+        let walk = atlas.animation("walk").unwrap().iter().map(|frame| frame.x).collect::<Vec<_>>();
+        assert_eq!(walk, vec![0, 10, 20]);
+        assert_eq!(atlas.animation("hurt").unwrap()[0].duration, None);
+        assert_eq!(atlas.frames().len(), 4);
+    }
+
+    #[test]
+    fn tags_must_be_inside_the_atlas() {
+        let json = r#"{
+            "frames": [ { "filename": "a", "frame": { "x": 0, "y": 0, "w": 1, "h": 1 } } ],
+            "meta": { "frameTags": [ { "name": "walk", "from": 0, "to": 1 } ] }
+        }"#;
+        assert!(SpriteAtlas::parse(json).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Debug;
+use std::time::Duration;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use rand::rngs::ThreadRng;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use crate::assets::geometry::Material;
+use crate::characters::{Character, CharacterPhysics, CharacterType, CharacterWorldState};
+use crate::characters::effect::DeathEffect;
+use crate::characters::interaction::Interaction;
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::CharacterSprites;
+
+/// Everything the game needs to know about a kind of character, registered in `registry`
+pub trait CharacterBehaviour: Sync {
+    fn character_type(&self) -> CharacterType;
+
+    /// unique name used in the config & saved games
+    fn name(&self) -> String {
+        match self.character_type() {
+            CharacterType::BuiltIn(name) => name.to_string(),
+            character => format!("{:?}", character)
+        }
+    }
+
+    /// relative chance of this character being picked at random
+    fn weight(&self) -> f32 {
+        1.0
+    }
+
+    fn material(&self) -> Material {
+        Material::Default
+    }
+
+    /// zero for characters that fly
+    fn gravity_scale(&self) -> f32 {
+        1.0
+    }
+
+    /// pac-man eats it when powered up & jetman flees it
+    fn is_ghost(&self) -> bool {
+        false
+    }
+
+    /// bodies the character touches are destroyed
+    fn destroys_on_collision(&self) -> bool {
+        false
+    }
+
+    /// default for what this character does when it meets another, overridden in config
+    fn interaction(&self, _other: CharacterType) -> Interaction {
+        Interaction::Bounce
+    }
+
+    /// the character is able to eat the other right now, when its interaction is `Eat`
+    fn can_eat(&self, _me: &Character, _other: &Character) -> bool {
+        true
+    }
+
+    /// particles left behind where the character dies
+    fn death_effect(&self) -> DeathEffect {
+        DeathEffect::default()
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory;
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr;
+
+    /// state of a newly spawned character
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind>;
+
+    /// state of a character saved with `CharacterMind::save`
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String>;
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String>;
+
+    fn sound(&self) -> CharacterSoundData;
+}
+
+/// The state of a single character
+pub trait CharacterMind: Debug + Send + Sync {
+    /// called every frame after the lifetime of the character has been updated
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics;
+
+    fn is_powered(&self) -> bool {
+        false
+    }
+
+    fn save(&self) -> serde_json::Value;
+
+    fn clone_mind(&self) -> Box<dyn CharacterMind>;
+}
+
+impl Clone for Box<dyn CharacterMind> {
+    fn clone(&self) -> Self {
+        self.clone_mind()
+    }
+}
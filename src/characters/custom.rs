@@ -0,0 +1,233 @@
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use rand::rngs::ThreadRng;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use crate::assets::geometry::Material;
+use crate::characters::behaviour::{CharacterBehaviour, CharacterMind};
+use crate::characters::effect::DeathEffect;
+use crate::characters::{CharacterPhysics, CharacterType, CharacterWorldState};
+use crate::characters::definition::{CharacterAssets, CharacterDefinition, MovementModel};
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::pac_man::PacManDirection;
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::CharacterSprites;
+use crate::characters::steering;
+use crate::characters::steering::Wander;
+use crate::game::event::BodyKind;
+
+/// steering characters look this many seconds ahead for obstacles
+const LOOK_AHEAD_SECONDS: f32 = 1.0;
+/// steering characters keep this far from obstacles & walls
+const CLEARANCE_METERS: f32 = 0.5;
+/// radians per second a wandering character changes its mind by
+const WANDER_JITTER: f32 = 3.0;
+
+/// A character loaded from a definition file
+pub struct CustomBehaviour {
+    character_type: CharacterType,
+    definition: CharacterDefinition,
+    assets: CharacterAssets
+}
+
+impl CustomBehaviour {
+    pub fn new(character_type: CharacterType, definition: CharacterDefinition, assets: CharacterAssets) -> Self {
+        Self { character_type, definition, assets }
+    }
+}
+
+impl CharacterBehaviour for CustomBehaviour {
+    fn character_type(&self) -> CharacterType {
+        self.character_type
+    }
+
+    fn name(&self) -> String {
+        self.definition.name.clone()
+    }
+
+    fn weight(&self) -> f32 {
+        self.definition.weight
+    }
+
+    fn material(&self) -> Material {
+        self.definition.material
+    }
+
+    fn gravity_scale(&self) -> f32 {
+        self.definition.gravity_scale
+    }
+
+    fn destroys_on_collision(&self) -> bool {
+        self.definition.destroys_on_collision
+    }
+
+    fn death_effect(&self) -> DeathEffect {
+        self.definition.death_effect
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory {
+        self.definition.lifetime(&self.assets)
+    }
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        self.definition.shape(polygon_scale)
+    }
+
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind> {
+        let heading = match self.definition.movement {
+            MovementModel::FourWay { .. } => rng.gen::<PacManDirection>().angle(),
+            MovementModel::Steering { .. } => rng.gen_range(0.0..360.0),
+            MovementModel::Hopping { .. } => *[0.0, 180.0].choose(rng).unwrap()
+        };
+        Box::new(CustomState {
+            movement: self.definition.movement,
+            speed: self.definition.speed,
+            heading,
+            since_last_turn: 0.0,
+            wander: Wander::default()
+        })
+    }
+
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String> {
+        let mut state: CustomState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        // pick up any tuning since the character was saved
+        state.movement = self.definition.movement;
+        state.speed = self.definition.speed;
+        Ok(Box::new(state))
+    }
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        self.definition.sprites(&self.assets, texture_creator)
+    }
+
+    fn sound(&self) -> CharacterSoundData {
+        self.definition.sound(&self.assets, self.character_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomState {
+    movement: MovementModel,
+    /// meters per second
+    speed: f32,
+    /// degrees
+    heading: f32,
+    /// seconds since the last turn or hop
+    since_last_turn: f32,
+    #[serde(default)]
+    wander: Wander
+}
+
+impl CharacterMind for CustomState {
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics {
+        if !state.is_alive() {
+            return CharacterPhysics::new(0.0, B2vec2::zero());
+        }
+
+        let delta = delta.as_secs_f32();
+        self.since_last_turn += delta;
+        let speed = self.speed * polygon_scale;
+        let food = Self::food(&world_state);
+        match self.movement {
+            MovementModel::FourWay { turn_probability, feeding_turn_probability } => {
+                let direction = PacManDirection::towards(self.heading_vector());
+                let turn_to = if world_state.is_colliding {
+                    food.map(PacManDirection::towards)
+                        .filter(|d| *d != direction)
+                        .or_else(|| direction.other_directions().choose(rng).copied())
+                } else if let Some(food) = food {
+                    Some(PacManDirection::towards(food))
+                        .filter(|d| *d != direction && self.should_turn(rng, feeding_turn_probability))
+                } else if self.should_turn(rng, turn_probability) {
+                    Some(rng.gen())
+                } else {
+                    None
+                };
+                let direction = turn_to.unwrap_or(direction);
+                if direction.angle() != self.heading {
+                    self.heading = direction.angle();
+                    self.since_last_turn = 0.0;
+                }
+                CharacterPhysics::new(self.heading, direction.velocity(speed))
+            }
+            MovementModel::Steering { turn_rate } => {
+                let velocity = world_state.velocity();
+                let clearance = CLEARANCE_METERS * polygon_scale;
+                let goal = match (food, world_state.wall()) {
+                    (Some(food), _) => steering::seek(food, speed),
+                    (None, Some(wall)) if wall.length() < clearance * 2.0 => steering::follow_wall(velocity, wall, clearance, speed),
+                    _ => self.wander.update(rng, delta, velocity, WANDER_JITTER, speed)
+                };
+                // letters are food, anything else is in the way
+                let obstacles = world_state.nearby().iter()
+                    .filter(|body| body.kind != BodyKind::Alphanumeric)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let avoid = steering::avoid_obstacles(velocity, &obstacles, speed * LOOK_AHEAD_SECONDS, clearance, speed);
+                let flee = world_state.threats().iter()
+                    .find(|body| body.visible)
+                    .map_or(B2vec2::zero(), |threat| steering::flee(threat.offset, speed));
+                let desired = steering::combine(&[(goal, 1.0), (avoid, 2.0), (flee, 2.0)], speed);
+                if desired.length() > f32::EPSILON {
+                    self.heading = desired.y.atan2(desired.x).to_degrees().rem_euclid(360.0);
+                }
+                // turning in a circle at the turn rate needs this much acceleration
+                CharacterPhysics::new(self.heading, desired).with_steering(speed * turn_rate.to_radians())
+            }
+            MovementModel::Hopping { hop_seconds } => {
+                if world_state.is_colliding {
+                    self.heading = (self.heading + 180.0).rem_euclid(360.0);
+                }
+                if self.since_last_turn >= hop_seconds {
+                    // each hop heads for food when there is some
+                    if let Some(food) = food {
+                        self.heading = if food.x < 0.0 { 180.0 } else { 0.0 };
+                    }
+                    self.since_last_turn = 0.0;
+                }
+                // up at the start of the hop & down at the end
+                let progress = self.since_last_turn / hop_seconds.max(f32::EPSILON);
+                let horizontal = if self.heading == 180.0 { -speed } else { speed };
+                CharacterPhysics::new(0.0, B2vec2::new(horizontal, speed * (2.0 * progress - 1.0)))
+            }
+        }
+    }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn clone_mind(&self) -> Box<dyn CharacterMind> {
+        Box::new(self.clone())
+    }
+}
+
+impl CustomState {
+    fn heading_vector(&self) -> B2vec2 {
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        B2vec2::new(cos, sin)
+    }
+
+    fn should_turn(&self, rng: &mut ThreadRng, probability: f32) -> bool {
+        (self.since_last_turn * probability) > rng.gen::<f32>()
+    }
+
+    /// offset to the food in plain sight or else the closest food behind something
+    fn food(world_state: &CharacterWorldState) -> Option<B2vec2> {
+        world_state.nearby().iter()
+            .find(|body| body.visible && body.kind == BodyKind::Alphanumeric)
+            .map(|body| body.offset)
+            .or(world_state.closest_body.map(|d| B2vec2::zero() - d))
+    }
+}
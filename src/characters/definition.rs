@@ -0,0 +1,501 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use box2d_rs::shapes::b2_circle_shape::B2circleShape;
+use box2d_rs::shapes::b2_polygon_shape::B2polygonShape;
+use sdl2::rect::Rect;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use crate::assets::geometry::Material;
+use crate::characters::animation::{DIE, SpriteAnimationType, WALK};
+use crate::characters::atlas::SpriteAtlas;
+use crate::characters::CharacterType;
+use crate::characters::effect::DeathEffect;
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::{CharacterSprites, SpriteSheetFormat};
+
+/// A character described in a json file rather than in code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterDefinition {
+    /// unique name, used in the config & saved games
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub material: Material,
+    /// zero for characters that fly
+    #[serde(default = "default_gravity_scale")]
+    pub gravity_scale: f32,
+    /// letters the character touches are eaten
+    #[serde(default)]
+    pub destroys_on_collision: bool,
+    pub shape: ShapeDefinition,
+    /// meters per second
+    pub speed: f32,
+    pub movement: MovementModel,
+    pub lifetime: LifetimeDefinition,
+    /// loops while the character is alive
+    pub sprite: SpriteDefinition,
+    pub death: SpriteDefinition,
+    /// particles left behind where the character dies
+    #[serde(default)]
+    pub death_effect: DeathEffect,
+    /// the animation of each state by name, e.g. spawn, idle, sleep, eat & hurt,
+    /// states without one use the sprite
+    #[serde(default)]
+    pub animations: HashMap<String, SpriteDefinition>,
+    #[serde(default)]
+    pub sounds: SoundDefinition
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_gravity_scale() -> f32 {
+    1.0
+}
+
+/// Size of the character in meters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ShapeDefinition {
+    Circle { radius: f32 },
+    Box { width: f32, height: f32 }
+}
+
+/// How the character gets about
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MovementModel {
+    /// up, down, left & right like pac-man, turning towards food
+    FourWay { turn_probability: f32, feeding_turn_probability: f32 },
+    /// any direction, smoothly seeking food, wandering, following walls & avoiding obstacles,
+    /// turning at up to the turn rate in degrees per second
+    Steering { turn_rate: f32 },
+    /// short jumps that take the given time, towards food when there is some
+    Hopping { hop_seconds: f32 }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LifetimeDefinition {
+    /// average lifetime
+    pub seconds: f32,
+    /// each character lives for the average plus or minus half of this fraction of it
+    #[serde(default = "default_lifetime_variance")]
+    pub variance: f32
+}
+
+fn default_lifetime_variance() -> f32 {
+    0.25
+}
+
+/// A sprite sheet & how to play it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteDefinition {
+    /// png file relative to the definition
+    pub file: String,
+    /// not needed for atlases, grids default to every cell
+    #[serde(default)]
+    pub frames: usize,
+    #[serde(default)]
+    pub layout: SheetLayout,
+    pub animation: AnimationDefinition
+}
+
+/// Where the frames are on a sprite sheet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum SheetLayout {
+    /// equally sized frames side by side
+    #[default]
+    Linear,
+    /// equally sized frames read left to right then top to bottom
+    Grid { columns: usize, rows: usize },
+    /// aseprite or texture packer json export relative to the definition,
+    /// using the frames of the named tag or every frame
+    Atlas { file: String, animation: Option<String> }
+}
+
+/// How the frames of a sprite sheet are played
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AnimationDefinition {
+    Static,
+    Linear { frame_ms: u64 },
+    YoYo { frame_ms: u64 },
+    LinearWithPause { frame_ms: u64, pause_ms: u64, resume_from_frame: usize },
+    /// frame durations from an atlas
+    Atlas
+}
+
+/// frames without a duration in an atlas
+const DEFAULT_FRAME_MS: u64 = 100;
+
+/// Ogg files relative to the definition, all optional
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoundDefinition {
+    pub create: Option<String>,
+    pub destroy: Option<String>,
+    pub attack: Option<String>,
+    /// played as the character eats another character
+    pub eat: Option<String>,
+    /// played as the character powers up
+    pub power_up: Option<String>,
+    /// played as the character enters each state
+    #[serde(default)]
+    pub states: HashMap<CharacterState, String>
+}
+
+/// The files a definition refers to
+#[derive(Debug, Clone)]
+pub struct CharacterAssets {
+    sprite: (SpriteSheetFormat, SpriteAnimationType),
+    death: (SpriteSheetFormat, SpriteAnimationType),
+    animations: HashMap<String, (SpriteSheetFormat, SpriteAnimationType)>,
+    create: Option<&'static [u8]>,
+    destroy: Option<&'static [u8]>,
+    attack: Option<&'static [u8]>,
+    eat: Option<&'static [u8]>,
+    power_up: Option<&'static [u8]>,
+    states: Vec<(CharacterState, &'static [u8])>
+}
+
+impl CharacterDefinition {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let definition: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for name in [WALK, DIE] {
+            if definition.animations.contains_key(name) {
+                return Err(format!("{} is the sprite or death animation of {}", name, definition.name));
+            }
+        }
+        for sprite in [&definition.sprite, &definition.death].into_iter().chain(definition.animations.values()) {
+            sprite.validate().map_err(|e| format!("{} {}: {}", definition.name, sprite.file, e))?;
+        }
+        definition.validate().map_err(|e| format!("{}: {}", definition.name, e))?;
+        Ok(definition)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if !positive(self.lifetime.seconds) {
+            return Err(format!("lifetime of {} seconds must be more than zero", self.lifetime.seconds));
+        }
+        if !(0.0..=1.0).contains(&self.lifetime.variance) {
+            return Err(format!("lifetime variance of {} must be between 0 & 1", self.lifetime.variance));
+        }
+        let size_is_positive = match self.shape {
+            ShapeDefinition::Circle { radius } => positive(radius),
+            ShapeDefinition::Box { width, height } => positive(width) && positive(height)
+        };
+        if !size_is_positive {
+            return Err(format!("{:?} must be bigger than zero", self.shape));
+        }
+        if !positive(self.speed) {
+            return Err(format!("speed of {} must be more than zero", self.speed));
+        }
+        let probability = |value: f32| (0.0..=1.0).contains(&value);
+        let movement_makes_sense = match self.movement {
+            MovementModel::FourWay { turn_probability, feeding_turn_probability } =>
+                probability(turn_probability) && probability(feeding_turn_probability),
+            MovementModel::Steering { turn_rate } => positive(turn_rate),
+            MovementModel::Hopping { hop_seconds } => positive(hop_seconds)
+        };
+        if !movement_makes_sense {
+            return Err(format!("{:?} needs probabilities between 0 & 1 & rates or times more than zero", self.movement));
+        }
+        Ok(())
+    }
+
+    /// finds every file the definition refers to
+    pub fn assets<F: Fn(&str) -> Result<&'static [u8], String>>(&self, load: F) -> Result<CharacterAssets, String> {
+        let load_optional = |file: &Option<String>| file.as_deref().map(&load).transpose();
+        let mut animations = HashMap::new();
+        for (name, sprite) in &self.animations {
+            animations.insert(name.clone(), sprite.load(&load)?);
+        }
+        let mut states = vec![];
+        for (&state, file) in &self.sounds.states {
+            states.push((state, load(file)?));
+        }
+        Ok(CharacterAssets {
+            sprite: self.sprite.load(&load)?,
+            death: self.death.load(&load)?,
+            animations,
+            create: load_optional(&self.sounds.create)?,
+            destroy: load_optional(&self.sounds.destroy)?,
+            attack: load_optional(&self.sounds.attack)?,
+            eat: load_optional(&self.sounds.eat)?,
+            power_up: load_optional(&self.sounds.power_up)?,
+            states
+        })
+    }
+
+    pub fn lifetime(&self, assets: &CharacterAssets) -> CharacterLifetimeFactory {
+        let factory = CharacterLifetimeFactory::new(
+            Duration::from_secs_f32(self.lifetime.seconds),
+            assets.sprite.1.clone(),
+            assets.death.1.clone()
+        ).with_variance(self.lifetime.variance);
+        assets.animations.iter()
+            .fold(factory, |factory, (name, (_, animation))| factory.with_animation(name, animation.clone()))
+    }
+
+    pub fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        match self.shape {
+            ShapeDefinition::Circle { radius } => {
+                let mut shape = B2circleShape::default();
+                shape.base.m_radius = radius * polygon_scale;
+                Rc::new(RefCell::new(shape))
+            }
+            ShapeDefinition::Box { width, height } => {
+                let mut shape = B2polygonShape::default();
+                let (x, y) = (width * polygon_scale / 2.0, height * polygon_scale / 2.0);
+                shape.set(&[B2vec2::new(-x, -y), B2vec2::new(x, -y), B2vec2::new(x, y), B2vec2::new(-x, y)]);
+                Rc::new(RefCell::new(shape))
+            }
+        }
+    }
+
+    pub fn sprites<'a>(&self, assets: &CharacterAssets, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        let mut sprites = CharacterSprites::new(texture_creator, assets.sprite.0.clone(), assets.death.0.clone())?;
+        for (name, (format, _)) in &assets.animations {
+            sprites = sprites.with_animation(texture_creator, name, format.clone())?;
+        }
+        Ok(sprites)
+    }
+
+    pub fn sound(&self, assets: &CharacterAssets, character: CharacterType) -> CharacterSoundData {
+        let mut sound = CharacterSoundData::optional(character, assets.create, assets.destroy, assets.attack);
+        if let Some(eat) = assets.eat {
+            sound = sound.with_eat(eat);
+        }
+        if let Some(power_up) = assets.power_up {
+            sound = sound.with_power_up(power_up);
+        }
+        assets.states.iter().fold(sound, |sound, &(state, sample)| sound.with_state(state, sample))
+    }
+}
+
+impl SpriteDefinition {
+    fn validate(&self) -> Result<(), String> {
+        match self.layout {
+            SheetLayout::Linear if self.frames == 0 => Err("must have at least one frame".to_string()),
+            SheetLayout::Grid { columns, rows } if columns == 0 || rows == 0 || self.frames > columns * rows =>
+                Err(format!("{} frames don't fit in {} columns & {} rows", self.frames, columns, rows)),
+            SheetLayout::Linear | SheetLayout::Grid { .. } if self.animation == AnimationDefinition::Atlas =>
+                Err("only atlases have frame durations".to_string()),
+            _ => match self.animation {
+                AnimationDefinition::Linear { frame_ms: 0 }
+                | AnimationDefinition::YoYo { frame_ms: 0 }
+                | AnimationDefinition::LinearWithPause { frame_ms: 0, .. } => Err("frames must last at least 1 ms".to_string()),
+                _ => Ok(())
+            }
+        }
+    }
+
+    /// the sprite sheet format with the frames found in the atlas if there is one
+    fn load<F: Fn(&str) -> Result<&'static [u8], String>>(&self, load: F) -> Result<(SpriteSheetFormat, SpriteAnimationType), String> {
+        let file = load(&self.file)?;
+        match &self.layout {
+            SheetLayout::Linear =>
+                Ok((SpriteSheetFormat::exclusive_linear(file, self.frames), self.animation_type(self.frames, vec![])?)),
+            SheetLayout::Grid { columns, rows } => {
+                let frames = if self.frames == 0 { columns * rows } else { self.frames };
+                Ok((SpriteSheetFormat::grid(file, *columns, *rows, frames), self.animation_type(frames, vec![])?))
+            }
+            SheetLayout::Atlas { file: atlas_file, animation } => {
+                let json = std::str::from_utf8(load(atlas_file)?).map_err(|e| format!("{}: {}", atlas_file, e))?;
+                let atlas = SpriteAtlas::parse(json).map_err(|e| format!("{}: {}", atlas_file, e))?;
+                let frames = match animation {
+                    Some(name) => atlas.animation(name).ok_or_else(|| format!("{} has no animation {}", atlas_file, name))?,
+                    None => atlas.frames().to_vec()
+                };
+                let rects = frames.iter().map(|frame| Rect::new(frame.x, frame.y, frame.width, frame.height)).collect();
+                let durations = frames.iter()
+                    .map(|frame| frame.duration.unwrap_or(Duration::from_millis(DEFAULT_FRAME_MS)))
+                    .collect();
+                Ok((SpriteSheetFormat::atlas(file, rects), self.animation_type(frames.len(), durations)?))
+            }
+        }
+    }
+
+    /// frame counts are only known once an atlas is loaded so the frame to resume from is checked here
+    fn animation_type(&self, frames: usize, durations: Vec<Duration>) -> Result<SpriteAnimationType, String> {
+        if let AnimationDefinition::LinearWithPause { resume_from_frame, .. } = self.animation {
+            if resume_from_frame >= frames {
+                return Err(format!("{} cannot resume from frame {} of {}", self.file, resume_from_frame, frames));
+            }
+        }
+        let animation_type = match self.animation {
+            AnimationDefinition::Static => SpriteAnimationType::Static,
+            AnimationDefinition::Linear { frame_ms } =>
+                SpriteAnimationType::Linear { frames, duration: Duration::from_millis(frame_ms) },
+            AnimationDefinition::YoYo { frame_ms } =>
+                SpriteAnimationType::YoYo { frames, duration: Duration::from_millis(frame_ms) },
+            AnimationDefinition::LinearWithPause { frame_ms, pause_ms, resume_from_frame } =>
+                SpriteAnimationType::LinearWithPause {
+                    frames,
+                    duration: Duration::from_millis(frame_ms),
+                    pause_for: Duration::from_millis(pause_ms),
+                    resume_from_frame
+                },
+            AnimationDefinition::Atlas => SpriteAnimationType::Timed { durations },
+        };
+        Ok(animation_type)
+    }
+}
+
+/// every definition in the directory with the files they refer to, nothing if there is no directory,
+/// bad definitions are skipped
+pub fn load_definitions(path: &Path) -> Result<Vec<(CharacterDefinition, CharacterAssets)>, String> {
+    if !path.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut result = vec![];
+    for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
+        let file = entry.map_err(|e| e.to_string())?.path();
+        if file.extension().map_or(true, |extension| extension != "json") {
+            continue;
+        }
+        match load_definition(path, &file) {
+            Ok(definition) => result.push(definition),
+            Err(error) => println!("Bad character definition at {}, {}, skipping", file.display(), error)
+        }
+    }
+    Ok(result)
+}
+
+fn load_definition(path: &Path, file: &Path) -> Result<(CharacterDefinition, CharacterAssets), String> {
+    let json = fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let definition = CharacterDefinition::parse(&json)?;
+    // loaded once at startup & needed until the game exits
+    let assets = definition.assets(|name| {
+        let bytes = fs::read(path.join(name)).map_err(|e| format!("{}: {}", name, e))?;
+        Ok(Box::leak(bytes.into_boxed_slice()) as &'static [u8])
+    })?;
+    Ok((definition, assets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_pac_man_definition_parses() {
+        let definition = CharacterDefinition::parse(include_str!("pac_man/pac_man.json")).unwrap();
+        assert_eq!(definition.name, "PacMan");
+        assert!(matches!(definition.movement, MovementModel::FourWay { .. }));
+        assert!(matches!(definition.death_effect, DeathEffect::Pellets { .. }));
+    }
+
+    #[test]
+    fn optional_fields_have_defaults() {
+        let definition = CharacterDefinition::parse(r#"{
+            "name": "Frog",
+            "shape": { "Box": { "width": 0.3, "height": 0.2 } },
+            "speed": 1.5,
+            "movement": { "Hopping": { "hop_seconds": 0.8 } },
+            "lifetime": { "seconds": 15.0 },
+            "sprite": { "file": "frog.png", "frames": 2, "animation": { "Linear": { "frame_ms": 200 } } },
+            "death": { "file": "frog_death.png", "frames": 1, "animation": "Static" }
+        }"#).unwrap();
+        assert_eq!(definition.weight, 1.0);
+        assert_eq!(definition.gravity_scale, 1.0);
+        assert_eq!(definition.lifetime.variance, 0.25);
+        assert_eq!(definition.sounds, SoundDefinition::default());
+        assert_eq!(definition.death_effect, DeathEffect::Smoke);
+    }
+
+    #[test]
+    fn sprites_need_a_frame() {
+        let json = include_str!("pac_man/pac_man.json").replace("\"frames\": 3", "\"frames\": 0");
+        assert!(CharacterDefinition::parse(&json).is_err());
+    }
+
+    #[test]
+    fn grids_and_atlases_find_their_frames() {
+        let definition = CharacterDefinition::parse(r#"{
+            "name": "Frog",
+            "shape": { "Circle": { "radius": 0.2 } },
+            "speed": 1.5,
+            "movement": { "Hopping": { "hop_seconds": 0.8 } },
+            "lifetime": { "seconds": 15.0 },
+            "sprite": { "file": "frog.png", "layout": { "Grid": { "columns": 3, "rows": 2 } }, "animation": { "Linear": { "frame_ms": 200 } } },
+            "death": { "file": "frog.png", "frames": 5, "layout": { "Grid": { "columns": 3, "rows": 2 } }, "animation": "Static" },
+            "animations": {
+                "eat": { "file": "frog.png", "layout": { "Atlas": { "file": "frog.json", "animation": "eat" } }, "animation": "Atlas" }
+            },
+            "sounds": { "states": { "Sleeping": "snore.ogg" } }
+        }"#).unwrap();
+        let assets = definition.assets(|file| match file {
+            "frog.json" => Ok(r#"{
+                "frames": [
+                    { "filename": "eat 0", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "duration": 50 },
+                    { "filename": "eat 1", "frame": { "x": 8, "y": 0, "w": 8, "h": 8 } }
+                ]
+            }"#.as_bytes()),
+            _ => Ok(&[])
+        }).unwrap();
+        assert!(matches!(assets.sprite.1, SpriteAnimationType::Linear { frames: 6, .. }));
+        assert_eq!(assets.states.len(), 1);
+        match &assets.animations["eat"].1 {
+            SpriteAnimationType::Timed { durations } =>
+                assert_eq!(durations, &vec![Duration::from_millis(50), Duration::from_millis(DEFAULT_FRAME_MS)]),
+            animation => panic!("eat should be timed, not {:?}", animation)
+        }
+    }
+
+    #[test]
+    fn sizes_speeds_and_lifetimes_must_make_sense() {
+        let pac_man = include_str!("pac_man/pac_man.json");
+        let lifetime = r#""lifetime": { "seconds": 20.0, "variance": 0.25 }"#;
+        assert!(pac_man.contains(lifetime));
+        for bad in [r#""lifetime": { "seconds": -1.0 }"#, r#""lifetime": { "seconds": 0.0 }"#, r#""lifetime": { "seconds": 20.0, "variance": 2.5 }"#] {
+            assert!(CharacterDefinition::parse(&pac_man.replace(lifetime, bad)).is_err(), "{}", bad);
+        }
+        let frog = |shape: &str, speed: &str| format!(r#"{{
+            "name": "Frog",
+            "shape": {},
+            "speed": {},
+            "movement": {{ "Hopping": {{ "hop_seconds": 0.8 }} }},
+            "lifetime": {{ "seconds": 15.0 }},
+            "sprite": {{ "file": "frog.png", "frames": 1, "animation": "Static" }},
+            "death": {{ "file": "frog.png", "frames": 1, "animation": "Static" }}
+        }}"#, shape, speed);
+        assert!(CharacterDefinition::parse(&frog(r#"{ "Box": { "width": 0.3, "height": 0.2 } }"#, "1.5")).is_ok());
+        assert!(CharacterDefinition::parse(&frog(r#"{ "Box": { "width": 0.3, "height": 0.0 } }"#, "1.5")).is_err());
+        assert!(CharacterDefinition::parse(&frog(r#"{ "Circle": { "radius": -0.2 } }"#, "1.5")).is_err());
+        assert!(CharacterDefinition::parse(&frog(r#"{ "Circle": { "radius": 0.2 } }"#, "0.0")).is_err());
+    }
+
+    #[test]
+    fn frames_movement_and_pauses_must_make_sense() {
+        let pac_man = include_str!("pac_man/pac_man.json");
+        let sprite = r#"{ "YoYo": { "frame_ms": 100 } }"#;
+        let movement = r#"{ "FourWay": { "turn_probability": 0.01, "feeding_turn_probability": 0.05 } }"#;
+        assert!(pac_man.contains(sprite) && pac_man.contains(movement));
+        for bad in [r#"{ "YoYo": { "frame_ms": 0 } }"#, r#"{ "Linear": { "frame_ms": 0 } }"#] {
+            assert!(CharacterDefinition::parse(&pac_man.replace(sprite, bad)).is_err(), "{}", bad);
+        }
+        for bad in [r#"{ "FourWay": { "turn_probability": -0.5, "feeding_turn_probability": 0.05 } }"#, r#"{ "Steering": { "turn_rate": 0.0 } }"#] {
+            assert!(CharacterDefinition::parse(&pac_man.replace(movement, bad)).is_err(), "{}", bad);
+        }
+
+        let pause = |resume_from_frame: usize| pac_man.replace(sprite, &format!(
+            r#"{{ "LinearWithPause": {{ "frame_ms": 100, "pause_ms": 200, "resume_from_frame": {} }} }}"#, resume_from_frame
+        ));
+        let assets = |json: &str| CharacterDefinition::parse(json).unwrap().assets(|_| Ok(&[][..])).map(|_| ());
+        assert!(assets(&pause(2)).is_ok());
+        assert!(assets(&pause(3)).is_err());
+    }
+
+    #[test]
+    fn only_atlases_have_frame_durations() {
+        let json = include_str!("pac_man/pac_man.json").replace("{ \"YoYo\": { \"frame_ms\": 100 } }", "\"Atlas\"");
+        assert!(CharacterDefinition::parse(&json).is_err());
+    }
+}
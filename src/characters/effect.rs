@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// Particles left behind where a character dies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum DeathEffect {
+    /// a puff of smoke
+    #[default]
+    Smoke,
+    /// sparks flying off in every direction
+    Sparks { color: (u8, u8, u8) },
+    /// dissolves into pellets that fall away
+    Pellets { color: (u8, u8, u8) },
+    /// nothing at all
+    Vanish
+}
@@ -0,0 +1,251 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use box2d_rs::shapes::b2_circle_shape::B2circleShape;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use rand::rngs::ThreadRng;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use crate::characters::animation::SpriteAnimationType;
+use crate::characters::behaviour::{CharacterBehaviour, CharacterMind};
+use crate::characters::effect::DeathEffect;
+use crate::characters::interaction::Interaction;
+use crate::characters::{Character, CharacterPhysics, CharacterType, CharacterWorldState, PerceivedBody};
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::pac_man::PacManDirection;
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::{CharacterSprites, SpriteSheetFormat};
+use crate::game::event::BodyKind;
+
+const SPRITE_DEATH: &[u8] = include_bytes!("death.png");
+
+const GHOST_RADIUS_METERS: f32 = 0.2;
+const GHOST_LIFETIME: f32 = 30.0;
+/// a little slower than pac-man so he can get away
+const GHOST_SPEED: f32 = 0.9;
+const GHOST_FRAMES: usize = 2;
+const GHOST_DEATH_FRAMES: usize = 4;
+const TURN_PROBABILITY: f32 = 0.01;
+const HUNTING_TURN_PROBABILITY: f32 = 0.1;
+/// how far ahead of pac-man an ambushing ghost aims, in seconds of pac-man's movement
+const AMBUSH_LOOKAHEAD: f32 = 2.0;
+/// how long a patrolling ghost walks each side of its beat
+const PATROL_LEG_SECONDS: f32 = 3.0;
+/// a patrolling ghost gives chase when pac-man is this close
+const PATROL_CHASE_METERS: f32 = 3.0;
+
+/// How a ghost picks where to go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GhostPersonality {
+    /// straight for pac-man
+    Chase,
+    /// for where pac-man is heading
+    Ambush,
+    /// walks a square beat, chasing pac-man when he comes close
+    Patrol,
+    /// wanders about
+    Random
+}
+
+impl CharacterType {
+    pub const BLINKY: CharacterType = CharacterType::BuiltIn("Blinky");
+    pub const PINKY: CharacterType = CharacterType::BuiltIn("Pinky");
+    pub const INKY: CharacterType = CharacterType::BuiltIn("Inky");
+    pub const CLYDE: CharacterType = CharacterType::BuiltIn("Clyde");
+}
+
+pub struct Ghost {
+    character_type: CharacterType,
+    personality: GhostPersonality,
+    sprite: &'static [u8],
+    /// of the sparks the ghost goes out in
+    color: (u8, u8, u8)
+}
+
+pub const BLINKY: Ghost = Ghost {
+    character_type: CharacterType::BLINKY,
+    personality: GhostPersonality::Chase,
+    sprite: include_bytes!("blinky.png"),
+    color: (0xFF, 0x00, 0x00)
+};
+
+pub const PINKY: Ghost = Ghost {
+    character_type: CharacterType::PINKY,
+    personality: GhostPersonality::Ambush,
+    sprite: include_bytes!("pinky.png"),
+    color: (0xFF, 0xB8, 0xFF)
+};
+
+pub const INKY: Ghost = Ghost {
+    character_type: CharacterType::INKY,
+    personality: GhostPersonality::Patrol,
+    sprite: include_bytes!("inky.png"),
+    color: (0x00, 0xFF, 0xFF)
+};
+
+pub const CLYDE: Ghost = Ghost {
+    character_type: CharacterType::CLYDE,
+    personality: GhostPersonality::Random,
+    sprite: include_bytes!("clyde.png"),
+    color: (0xFF, 0xB8, 0x52)
+};
+
+impl CharacterBehaviour for Ghost {
+    fn character_type(&self) -> CharacterType {
+        self.character_type
+    }
+
+    fn weight(&self) -> f32 {
+        0.5
+    }
+
+    fn is_ghost(&self) -> bool {
+        true
+    }
+
+    fn interaction(&self, other: CharacterType) -> Interaction {
+        match other {
+            CharacterType::PAC_MAN => Interaction::Eat,
+            // ghosts float through each other
+            _ if other.is_ghost() => Interaction::Ignore,
+            _ => Interaction::Bounce
+        }
+    }
+
+    fn can_eat(&self, _me: &Character, other: &Character) -> bool {
+        !other.is_powered()
+    }
+
+    fn death_effect(&self) -> DeathEffect {
+        DeathEffect::Sparks { color: self.color }
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory {
+        CharacterLifetimeFactory::new(
+            Duration::from_secs_f32(GHOST_LIFETIME),
+            SpriteAnimationType::Linear {
+                duration: Duration::from_millis(150),
+                frames: GHOST_FRAMES
+            },
+            SpriteAnimationType::Linear {
+                duration: Duration::from_millis(150),
+                frames: GHOST_DEATH_FRAMES
+            },
+        )
+    }
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        let mut shape = B2circleShape::default();
+        shape.base.m_radius = GHOST_RADIUS_METERS * polygon_scale;
+        Rc::new(RefCell::new(shape))
+    }
+
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind> {
+        Box::new(GhostState::new(rng, self.personality))
+    }
+
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String> {
+        let state: GhostState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        Ok(Box::new(state))
+    }
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        CharacterSprites::new(
+            texture_creator,
+            SpriteSheetFormat::exclusive_linear(self.sprite, GHOST_FRAMES),
+            SpriteSheetFormat::exclusive_linear(SPRITE_DEATH, GHOST_DEATH_FRAMES),
+        )
+    }
+
+    fn sound(&self) -> CharacterSoundData {
+        // pac-man makes the noise when a ghost is eaten
+        CharacterSoundData::silent(self.character_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostState {
+    personality: GhostPersonality,
+    direction: PacManDirection,
+    since_last_turn: f32
+}
+
+impl CharacterMind for GhostState {
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics {
+        if !state.is_alive() {
+            return CharacterPhysics::new(0.0, B2vec2::zero());
+        }
+
+        self.since_last_turn += delta.as_secs_f32();
+        if world_state.is_colliding {
+            self.direction = self.direction.other_directions().choose(rng).copied().unwrap();
+            self.since_last_turn = 0.0;
+        } else if let Some(target) = self.target(&world_state) {
+            let direction = PacManDirection::towards(target);
+            if self.direction != direction && self.should_turn(rng, HUNTING_TURN_PROBABILITY) {
+                self.direction = direction;
+                self.since_last_turn = 0.0;
+            }
+        } else if self.personality == GhostPersonality::Patrol {
+            if self.since_last_turn >= PATROL_LEG_SECONDS {
+                self.direction = self.direction.clockwise();
+                self.since_last_turn = 0.0;
+            }
+        } else if self.should_turn(rng, TURN_PROBABILITY) {
+            self.direction = rng.gen();
+            self.since_last_turn = 0.0;
+        }
+
+        // ghosts stay upright whichever way they go
+        CharacterPhysics::new(0.0, self.direction.velocity(polygon_scale * GHOST_SPEED))
+    }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn clone_mind(&self) -> Box<dyn CharacterMind> {
+        Box::new(self.clone())
+    }
+}
+
+impl GhostState {
+    pub fn new(rng: &mut ThreadRng, personality: GhostPersonality) -> Self {
+        Self { personality, direction: rng.gen(), since_last_turn: 0.0 }
+    }
+
+    fn should_turn(&self, rng: &mut ThreadRng, probability: f32) -> bool {
+        (self.since_last_turn * probability) > rng.gen::<f32>()
+    }
+
+    /// offset from the ghost to where it wants to go
+    fn target(&self, world_state: &CharacterWorldState) -> Option<B2vec2> {
+        let pac_man = Self::pac_man(world_state)?;
+        match self.personality {
+            GhostPersonality::Chase => Some(pac_man.offset),
+            GhostPersonality::Ambush => {
+                let mut ahead = pac_man.velocity;
+                ahead *= AMBUSH_LOOKAHEAD;
+                Some(pac_man.offset + ahead)
+            }
+            GhostPersonality::Patrol if pac_man.distance < PATROL_CHASE_METERS => Some(pac_man.offset),
+            GhostPersonality::Patrol | GhostPersonality::Random => None
+        }
+    }
+
+    fn pac_man(world_state: &CharacterWorldState) -> Option<&PerceivedBody> {
+        world_state.characters().iter()
+            .find(|body| body.visible && body.kind == BodyKind::Character(CharacterType::PAC_MAN))
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::characters::CharacterType;
+use crate::characters::registry::behaviours;
+
+/// What a character does when it meets another character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Interaction {
+    /// pass straight through each other
+    Ignore,
+    /// collide & turn away
+    Bounce,
+    /// the other character dies, if the behaviour says it can be eaten right now
+    Eat,
+    /// collide & steer away from the other character on sight
+    Flee,
+    /// collide without turning away
+    Befriend
+}
+
+/// How each character type treats every other character type
+#[derive(Debug, Clone)]
+pub struct InteractionMatrix {
+    interactions: HashMap<(CharacterType, CharacterType), Interaction>
+}
+
+impl InteractionMatrix {
+    /// pairs missing from the overrides keep the default from the behaviour of the subject
+    pub fn new(overrides: &HashMap<CharacterType, HashMap<CharacterType, Interaction>>) -> Self {
+        let mut interactions = HashMap::new();
+        for subject in behaviours() {
+            for other in behaviours() {
+                let key = (subject.character_type(), other.character_type());
+                let interaction = overrides.get(&key.0)
+                    .and_then(|row| row.get(&key.1))
+                    .copied()
+                    .unwrap_or_else(|| subject.interaction(key.1));
+                interactions.insert(key, interaction);
+            }
+        }
+        Self { interactions }
+    }
+
+    /// what the subject does to the other character
+    pub fn get(&self, subject: CharacterType, other: CharacterType) -> Interaction {
+        self.interactions.get(&(subject, other)).copied().unwrap_or(Interaction::Bounce)
+    }
+
+    /// the characters touch unless either ignores the other
+    pub fn collides(&self, a: CharacterType, b: CharacterType) -> bool {
+        self.get(a, b) != Interaction::Ignore && self.get(b, a) != Interaction::Ignore
+    }
+}
+
+impl Default for InteractionMatrix {
+    fn default() -> Self {
+        Self::new(&HashMap::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_defaults() {
+        let overrides = HashMap::from([
+            (CharacterType::PAC_MAN, HashMap::from([(CharacterType::BLINKY, Interaction::Befriend)]))
+        ]);
+        let matrix = InteractionMatrix::new(&overrides);
+        assert_eq!(matrix.get(CharacterType::PAC_MAN, CharacterType::BLINKY), Interaction::Befriend);
+        assert_eq!(matrix.get(CharacterType::PAC_MAN, CharacterType::PINKY), Interaction::Eat);
+    }
+
+    #[test]
+    fn either_ignoring_stops_a_collision() {
+        let overrides = HashMap::from([
+            (CharacterType::SNAKE, HashMap::from([(CharacterType::PAC_MAN, Interaction::Ignore)]))
+        ]);
+        let matrix = InteractionMatrix::new(&overrides);
+        assert!(!matrix.collides(CharacterType::PAC_MAN, CharacterType::SNAKE));
+        assert!(!matrix.collides(CharacterType::SNAKE, CharacterType::PAC_MAN));
+        assert!(matrix.collides(CharacterType::PAC_MAN, CharacterType::BLINKY));
+    }
+}
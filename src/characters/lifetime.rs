@@ -1,28 +1,83 @@
+use std::collections::HashMap;
 use std::time::Duration;
 use rand::prelude::ThreadRng;
 use rand::Rng;
-use crate::characters::animation::{SpriteAnimation, SpriteAnimationType};
+use serde::{Deserialize, Serialize};
+use crate::characters::animation::{DIE, EAT, HURT, IDLE, SLEEP, SPAWN, SpriteAnimation, SpriteAnimationType, WALK};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// how long states last for characters without an animation to play through
+const SPAWN_SECONDS: f32 = 0.5;
+const ATTACK_SECONDS: f32 = 0.5;
+const STUN_SECONDS: f32 = 1.5;
+const DEATH_SECONDS: f32 = 0.5;
+/// idle characters fall asleep after this long
+const SLEEP_AFTER_SECONDS: f32 = 5.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CharacterState {
+    /// just created, can't be eaten or stunned until the spawn animation has played
+    Spawning,
     Alive,
+    /// nothing nearby
+    Idle,
+    /// idle for a while, doesn't move until something comes near
+    Sleeping,
+    /// eating something
+    Attacking,
+    /// knocked about by a heavy collision or an explosion, doesn't move for a while
+    Stunned,
     Death,
     Dead
 }
 
 impl CharacterState {
     pub fn is_alive(&self) -> bool {
-        self == &Self::Alive
+        !matches!(self, Self::Death | Self::Dead)
+    }
+
+    /// can be eaten or stunned
+    pub fn is_vulnerable(&self) -> bool {
+        self.is_alive() && self != &Self::Spawning
+    }
+
+    /// alive but not moving
+    pub fn is_still(&self) -> bool {
+        matches!(self, Self::Sleeping | Self::Stunned)
+    }
+
+    /// name of the animation played in this state, see `animation`
+    pub fn animation(&self) -> &'static str {
+        match self {
+            Self::Spawning => SPAWN,
+            Self::Alive => WALK,
+            Self::Idle => IDLE,
+            Self::Sleeping => SLEEP,
+            Self::Attacking => EAT,
+            Self::Stunned => HURT,
+            Self::Death | Self::Dead => DIE
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CharacterLifetime {
     max_lifetime: Duration,
     duration: Duration,
     state: CharacterState,
     animation: SpriteAnimation,
-    death_animation: SpriteAnimation
+    death_animation: SpriteAnimation,
+    /// animations for the other states by name, states without one use the walk animation
+    #[serde(default)]
+    named: HashMap<String, SpriteAnimation>,
+    /// time since the state changed
+    #[serde(default)]
+    in_state: Duration,
+    /// nothing was near the character at the last update
+    #[serde(default)]
+    alone: bool,
+    /// state changes since they were last taken, see `take_transitions`
+    #[serde(skip)]
+    transitions: Vec<(CharacterState, CharacterState)>
 }
 
 impl CharacterLifetime {
@@ -30,81 +85,252 @@ impl CharacterLifetime {
         Self {
             max_lifetime,
             duration: Duration::ZERO,
-            state: CharacterState::Alive,
+            state: CharacterState::Spawning,
             animation,
             death_animation,
+            named: HashMap::new(),
+            in_state: Duration::ZERO,
+            alone: false,
+            transitions: vec![],
         }
     }
 
+    pub fn with_animations(self, named: HashMap<String, SpriteAnimation>) -> Self {
+        Self { named, ..self }
+    }
+
     pub fn duration(&self) -> Duration {
         self.duration
     }
 
     pub fn update(&mut self, delta: Duration) -> CharacterState {
         self.duration += delta;
-        match self.state {
-            CharacterState::Alive => {
-                if self.duration < self.max_lifetime {
+        self.in_state += delta;
+        if self.state.is_alive() && self.duration >= self.max_lifetime {
+            self.enter(CharacterState::Death);
+            return self.state;
+        }
+        let played = self.update_animation(delta);
+        let in_state = self.in_state.as_secs_f32();
+        let next = match self.state {
+            CharacterState::Spawning if played.unwrap_or(in_state >= SPAWN_SECONDS) => Some(CharacterState::Alive),
+            CharacterState::Attacking if played.unwrap_or(in_state >= ATTACK_SECONDS) => Some(CharacterState::Alive),
+            CharacterState::Stunned if in_state >= STUN_SECONDS => Some(CharacterState::Alive),
+            CharacterState::Alive if self.alone => Some(CharacterState::Idle),
+            CharacterState::Idle if !self.alone => Some(CharacterState::Alive),
+            CharacterState::Idle if in_state >= SLEEP_AFTER_SECONDS => Some(CharacterState::Sleeping),
+            CharacterState::Sleeping if !self.alone => Some(CharacterState::Alive),
+            // run for one iteration of the death animation
+            CharacterState::Death if played.unwrap_or(in_state >= DEATH_SECONDS) => Some(CharacterState::Dead),
+            _ => None
+        };
+        if let Some(next) = next {
+            self.enter(next);
+        }
+        self.state
+    }
+
+    /// whether the animation of the state has played through,
+    /// none if the state has no animation of its own or it's static & so never plays through
+    fn update_animation(&mut self, delta: Duration) -> Option<bool> {
+        let animation = match self.state {
+            CharacterState::Death | CharacterState::Dead => &mut self.death_animation,
+            state => match self.named.get_mut(state.animation()) {
+                Some(animation) => animation,
+                None => {
                     self.animation.update(delta);
-                } else {
-                    self.state = CharacterState::Death
-                }
-            }
-            CharacterState::Death => {
-                self.death_animation.update(delta);
-                // run for one iteration of the death animation
-                if self.death_animation.iteration() > 0 {
-                    self.state = CharacterState::Dead
+                    return None;
                 }
             }
-            CharacterState::Dead => {}
+        };
+        animation.update(delta);
+        if animation.is_static() {
+            None
+        } else {
+            Some(animation.iteration() > 0)
         }
-        self.state
     }
 
-    pub fn animation_frame(&self) -> (CharacterState, usize) {
-        let frame = match self.state {
-            CharacterState::Alive => self.animation.frame(),
-            CharacterState::Death | CharacterState::Dead => self.death_animation.frame(),
-        };
-        (self.state, frame)
+    fn enter(&mut self, state: CharacterState) {
+        if state == self.state {
+            return;
+        }
+        self.transitions.push((self.state, state));
+        self.state = state;
+        self.in_state = Duration::ZERO;
+        if let Some(animation) = self.named.get_mut(state.animation()) {
+            animation.restart();
+        }
     }
 
+    /// name of the animation to draw & its frame
+    pub fn animation_frame(&self) -> (&'static str, usize) {
+        match self.state {
+            CharacterState::Death | CharacterState::Dead => (DIE, self.death_animation.frame()),
+            state => match self.named.get(state.animation()) {
+                Some(animation) => (state.animation(), animation.frame()),
+                None => (WALK, self.animation.frame())
+            }
+        }
+    }
 
     pub fn state(&self) -> CharacterState {
         self.state
     }
 
+    /// (from, to) for every state change since the last time they were taken
+    pub fn take_transitions(&mut self) -> Vec<(CharacterState, CharacterState)> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// whether anything is near the character, it idles & then sleeps when there isn't
+    pub fn set_alone(&mut self, alone: bool) {
+        self.alone = alone;
+    }
+
+    /// the character is eating something
+    pub fn attack(&mut self) {
+        if self.state.is_vulnerable() {
+            self.enter(CharacterState::Attacking);
+        } else if self.state == CharacterState::Attacking {
+            self.in_state = Duration::ZERO;
+        }
+    }
+
+    /// the character was knocked about, ignored while spawning
+    pub fn stun(&mut self) {
+        if self.state.is_vulnerable() {
+            self.enter(CharacterState::Stunned);
+        }
+    }
+
     pub fn destroy(&mut self) {
         if self.state.is_alive() {
-            self.state = CharacterState::Death;
+            self.enter(CharacterState::Death);
         }
     }
 }
 
 const LIFETIME_VARIANCE: f32 = 0.25;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct CharacterLifetimeFactory {
     average_lifetime: Duration,
+    variance: f32,
     animation: SpriteAnimationType,
     death_animation: SpriteAnimationType,
+    named: HashMap<String, SpriteAnimationType>,
 }
 
 impl CharacterLifetimeFactory {
     pub fn new(average_lifetime: Duration, animation: SpriteAnimationType, death_animation: SpriteAnimationType) -> Self {
-        Self { average_lifetime, animation, death_animation }
+        Self { average_lifetime, variance: LIFETIME_VARIANCE, animation, death_animation, named: HashMap::new() }
+    }
+
+    /// the animation of another state, e.g. `animation::EAT` while attacking
+    pub fn with_animation(mut self, name: &str, animation: SpriteAnimationType) -> Self {
+        self.named.insert(name.to_string(), animation);
+        self
+    }
+
+    /// fraction of the average lifetime that each character's lifetime varies by
+    pub fn with_variance(self, variance: f32) -> Self {
+        Self { variance: variance.max(0.0), ..self }
     }
 
     pub fn new_lifetime(&self, rng: &mut ThreadRng) -> CharacterLifetime {
         let lifetime_secs = self.average_lifetime.as_secs_f32();
-        let range = lifetime_secs * LIFETIME_VARIANCE;
+        let range = lifetime_secs * self.variance;
         let lifetime_offset = rng.gen::<f32>() * range - range / 2.0;
-        let max_lifetime = Duration::from_secs_f32(lifetime_secs + lifetime_offset);
+        let max_lifetime = Duration::from_secs_f32((lifetime_secs + lifetime_offset).max(0.0));
+        let named = self.named.iter()
+            .map(|(name, animation)| (name.clone(), animation.clone().into_animation()))
+            .collect();
         CharacterLifetime::new(
             max_lifetime,
-            self.animation.into_animation(),
-            self.death_animation.into_animation()
-        )
+            self.animation.clone().into_animation(),
+            self.death_animation.clone().into_animation()
+        ).with_animations(named)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use super::*;
+
+    fn frames(frames: usize) -> SpriteAnimationType {
+        SpriteAnimationType::Linear { frames, duration: Duration::from_millis(100) }
+    }
+
+    fn lifetime() -> CharacterLifetime {
+        CharacterLifetimeFactory::new(Duration::from_secs(60), frames(2), frames(3))
+            .with_animation(SPAWN, frames(2))
+            .with_animation(EAT, frames(4))
+            .new_lifetime(&mut thread_rng())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn spawns_then_walks() {
+        let mut lifetime = lifetime();
+        assert_eq!(lifetime.animation_frame(), (SPAWN, 0));
+        lifetime.update(Duration::from_millis(150));
+        assert_eq!(lifetime.state(), CharacterState::Spawning);
+        assert_eq!(lifetime.animation_frame(), (SPAWN, 1));
+        // can't be hurt while spawning
+        lifetime.stun();
+        lifetime.update(Duration::from_millis(100));
+        assert_eq!(lifetime.state(), CharacterState::Alive);
+        assert_eq!(lifetime.animation_frame(), (WALK, 0));
+        assert_eq!(lifetime.take_transitions(), vec![(CharacterState::Spawning, CharacterState::Alive)]);
+        assert!(lifetime.take_transitions().is_empty());
+    }
+
+    #[test]
+    fn states_without_an_animation_walk() {
+        let mut lifetime = lifetime();
+        lifetime.update(Duration::from_millis(200));
+        lifetime.stun();
+        assert_eq!(lifetime.state(), CharacterState::Stunned);
+        assert_eq!(lifetime.animation_frame().0, WALK);
+        lifetime.update(Duration::from_secs_f32(STUN_SECONDS));
+        assert_eq!(lifetime.state(), CharacterState::Alive);
+
+        lifetime.attack();
+        lifetime.update(Duration::from_millis(150));
+        assert_eq!(lifetime.animation_frame(), (EAT, 1));
+
+        lifetime.destroy();
+        assert_eq!(lifetime.animation_frame(), (DIE, 0));
+    }
+
+    #[test]
+    fn static_animations_use_the_state_timers() {
+        let mut lifetime = CharacterLifetimeFactory::new(Duration::from_secs(60), frames(2), SpriteAnimationType::Static)
+            .with_animation(SPAWN, SpriteAnimationType::Static)
+            .with_animation(EAT, SpriteAnimationType::Static)
+            .new_lifetime(&mut thread_rng());
+        lifetime.update(Duration::from_secs_f32(SPAWN_SECONDS));
+        assert_eq!(lifetime.state(), CharacterState::Alive);
+        lifetime.attack();
+        lifetime.update(Duration::from_secs_f32(ATTACK_SECONDS));
+        assert_eq!(lifetime.state(), CharacterState::Alive);
+        lifetime.destroy();
+        lifetime.update(Duration::from_secs_f32(DEATH_SECONDS));
+        assert_eq!(lifetime.state(), CharacterState::Dead);
+    }
+
+    #[test]
+    fn idles_then_sleeps_when_alone() {
+        let mut lifetime = lifetime();
+        lifetime.update(Duration::from_millis(200));
+        lifetime.set_alone(true);
+        lifetime.update(Duration::from_millis(10));
+        assert_eq!(lifetime.state(), CharacterState::Idle);
+        lifetime.update(Duration::from_secs_f32(SLEEP_AFTER_SECONDS));
+        assert_eq!(lifetime.state(), CharacterState::Sleeping);
+        lifetime.set_alone(false);
+        lifetime.update(Duration::from_millis(10));
+        assert_eq!(lifetime.state(), CharacterState::Alive);
+    }
+}
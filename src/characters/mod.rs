@@ -1,17 +1,33 @@
 use std::time::Duration;
 use box2d_rs::b2_math::B2vec2;
 use box2d_rs::b2_shape::{ShapeDefPtr};
-use rand::distributions::Standard;
-use rand::prelude::Distribution;
 use rand::rngs::ThreadRng;
-use rand::{Rng, thread_rng};
-use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
-use crate::characters::pac_man::{pac_man_lifetime, pac_man_shape, pac_man_sound, PacManDirection, PacManState};
+use rand::thread_rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use crate::assets::geometry::Material;
+use crate::characters::behaviour::CharacterMind;
+use crate::characters::effect::DeathEffect;
+use crate::characters::interaction::{Interaction, InteractionMatrix};
+use crate::characters::lifetime::{CharacterLifetime, CharacterState};
+use crate::characters::registry::{behaviour, by_name};
 use crate::characters::sound::CharacterSound;
 use crate::config::{AudioConfig, PhysicsConfig};
+use crate::game::event::BodyKind;
 
 mod pac_man;
+mod ghost;
+mod snake;
+mod solar_jetman;
+mod custom;
+pub mod definition;
+pub mod steering;
+pub mod behaviour;
+pub mod registry;
+pub mod interaction;
+pub mod effect;
 mod sprites;
+mod atlas;
 pub mod render;
 mod animation;
 pub mod lifetime;
@@ -19,118 +35,375 @@ mod sound;
 
 pub struct CharacterPhysics {
     angle: f32,
-    velocity: B2vec2
+    velocity: B2vec2,
+    growth: Vec<(u8, u8, u8)>,
+    dies: bool,
+    grab: Option<u128>,
+    release: bool,
+    powers_up: bool,
+    max_acceleration: Option<f32>
 }
 
 impl CharacterPhysics {
     pub fn new(angle: f32, velocity: B2vec2) -> Self {
-        Self { angle, velocity }
+        Self { angle, velocity, growth: vec![], dies: false, grab: None, release: false, powers_up: false, max_acceleration: None }
+    }
+
+    /// add a segment of each color to the end of the character
+    pub fn with_growth(self, growth: Vec<(u8, u8, u8)>) -> Self {
+        Self { growth, ..self }
+    }
+
+    /// the character dies before its lifetime is up
+    pub fn with_death(self) -> Self {
+        Self { dies: true, ..self }
+    }
+
+    /// hold on to another body until it is released
+    pub fn with_grab(self, id: u128) -> Self {
+        Self { grab: Some(id), ..self }
+    }
+
+    /// the velocity is where the character wants to go, see `steering`,
+    /// it is reached by changing the current velocity no faster than the max acceleration
+    pub fn with_steering(self, max_acceleration: f32) -> Self {
+        Self { max_acceleration: Some(max_acceleration), ..self }
+    }
+
+    /// let go of whatever the character is holding
+    pub fn with_release(self) -> Self {
+        Self { release: true, ..self }
+    }
+
+    /// the character just ate a power pellet
+    pub fn with_power_up(self) -> Self {
+        Self { powers_up: true, ..self }
+    }
+
+    pub fn growth(&self) -> &[(u8, u8, u8)] {
+        &self.growth
+    }
+
+    pub fn dies(&self) -> bool {
+        self.dies
+    }
+
+    pub fn grab(&self) -> Option<u128> {
+        self.grab
+    }
+
+    pub fn releases(&self) -> bool {
+        self.release
+    }
+
+    pub fn powers_up(&self) -> bool {
+        self.powers_up
     }
 
     pub fn angle(&self) -> f32 {
         self.angle
     }
 
+    fn blended(self, velocity: B2vec2, delta: Duration) -> Self {
+        match self.max_acceleration {
+            Some(max_acceleration) => Self {
+                velocity: steering::blend(velocity, self.velocity, max_acceleration, delta.as_secs_f32()),
+                ..self
+            },
+            None => self
+        }
+    }
+
     pub fn velocity(&self) -> B2vec2 {
         self.velocity
     }
+
+    /// stays where it is & doesn't pick anything up
+    fn still(self) -> Self {
+        Self { velocity: B2vec2::zero(), grab: None, max_acceleration: None, ..self }
+    }
 }
 
+/// Names a kind of character, each built in one declares its own in its module
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
 pub enum CharacterType {
-    PacMan
+    /// built into the game, by name
+    BuiltIn(&'static str),
+    /// loaded from a definition file
+    Custom(CustomCharacter)
+}
+
+/// Index of a character loaded from a definition file, see `registry::register`
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CustomCharacter(u16);
+
+impl CustomCharacter {
+    pub fn new(index: u16) -> Self {
+        Self(index)
+    }
+}
+
+/// characters are saved by name so definition files can be added & removed
+impl Serialize for CharacterType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&behaviour(*self).name())
+    }
+}
+
+impl<'de> Deserialize<'de> for CharacterType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        by_name(&name).ok_or_else(|| D::Error::custom(format!("unknown character {}", name)))
+    }
 }
 
 impl CharacterType {
+    pub fn is_ghost(&self) -> bool {
+        behaviour(*self).is_ghost()
+    }
+
     pub fn destroys_on_collision(&self) -> bool {
-        match self {
-            CharacterType::PacMan => true
-        }
+        behaviour(*self).destroys_on_collision()
+    }
+
+    pub fn material(&self) -> Material {
+        behaviour(*self).material()
+    }
+
+    pub fn gravity_scale(&self) -> f32 {
+        behaviour(*self).gravity_scale()
     }
-}
 
-impl Distribution<CharacterType> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> CharacterType {
-        CharacterType::PacMan
+    pub fn death_effect(&self) -> DeathEffect {
+        behaviour(*self).death_effect()
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Character {
-    PacMan(PacManState)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "SavedCharacter", try_from = "SavedCharacter")]
+pub struct Character {
+    character_type: CharacterType,
+    lifetime: CharacterLifetime,
+    mind: Box<dyn CharacterMind>
 }
 
 impl Character {
     pub fn character_type(&self) -> CharacterType {
-        match self {
-            Character::PacMan(_) => CharacterType::PacMan
-        }
+        self.character_type
+    }
+
+    pub fn lifetime(&self) -> &CharacterLifetime {
+        &self.lifetime
     }
 
     pub fn destroy(&mut self) {
-        match self {
-            Character::PacMan(pac_man) => {
-                pac_man.lifetime_mut().destroy()
-            }
-        }
+        self.lifetime.destroy()
+    }
+
+    /// eating something
+    pub fn attack(&mut self) {
+        self.lifetime.attack()
+    }
+
+    /// knocked about by a heavy collision or an explosion
+    pub fn stun(&mut self) {
+        self.lifetime.stun()
+    }
+
+    /// (from, to) for every state change since the last time they were taken
+    pub fn take_transitions(&mut self) -> Vec<(CharacterState, CharacterState)> {
+        self.lifetime.take_transitions()
     }
 
     pub fn state(&self) -> CharacterState {
-        match self {
-            Character::PacMan(pac_man) => {
-                pac_man.lifetime().state()
-            }
-        }
+        self.lifetime.state()
     }
+
+    /// e.g. Pac-Man after eating a power pellet
+    pub fn is_powered(&self) -> bool {
+        self.mind.is_powered()
+    }
+
+    /// this character eats the other when they meet
+    pub fn eats(&self, other: &Character, interactions: &InteractionMatrix) -> bool {
+        self.state().is_alive()
+            && other.state().is_vulnerable()
+            && interactions.get(self.character_type, other.character_type) == Interaction::Eat
+            && behaviour(self.character_type).can_eat(self, other)
+    }
+}
+
+/// Characters are saved with their behaviour specific state as plain json
+#[derive(Serialize, Deserialize)]
+struct SavedCharacter {
+    character_type: CharacterType,
+    lifetime: CharacterLifetime,
+    state: serde_json::Value
+}
+
+impl From<Character> for SavedCharacter {
+    fn from(value: Character) -> Self {
+        Self { character_type: value.character_type, lifetime: value.lifetime, state: value.mind.save() }
+    }
+}
+
+impl TryFrom<SavedCharacter> for Character {
+    type Error = String;
+
+    fn try_from(value: SavedCharacter) -> Result<Self, Self::Error> {
+        let mind = behaviour(value.character_type).load(value.state)?;
+        Ok(Self { character_type: value.character_type, lifetime: value.lifetime, mind })
+    }
+}
+
+/// A body near a character
+#[derive(Debug, Clone, Copy)]
+pub struct PerceivedBody {
+    pub id: u128,
+    pub kind: BodyKind,
+    /// from the character to the body
+    pub offset: B2vec2,
+    pub velocity: B2vec2,
+    pub distance: f32,
+    /// nothing is in the way between the character & the body
+    pub visible: bool
 }
 
 pub struct CharacterWorldState {
     is_colliding: bool,
-    closest_body: Option<B2vec2>
+    closest_body: Option<B2vec2>,
+    nearby: Vec<PerceivedBody>,
+    characters: Vec<PerceivedBody>,
+    meals: Vec<(u8, u8, u8)>,
+    letters: Vec<char>,
+    touching_self: bool,
+    carrying: Option<u128>,
+    threats: Vec<PerceivedBody>,
+    velocity: B2vec2,
+    wall: Option<B2vec2>
 }
 
 impl CharacterWorldState {
-    pub fn new(is_colliding: bool, closest_body: Option<B2vec2>) -> Self {
-        Self { is_colliding, closest_body }
+    pub fn new(is_colliding: bool, closest_body: Option<B2vec2>, nearby: Vec<PerceivedBody>) -> Self {
+        Self { is_colliding, closest_body, nearby, characters: vec![], meals: vec![], letters: vec![], touching_self: false, carrying: None, threats: vec![], velocity: B2vec2::zero(), wall: None }
+    }
+
+    pub fn with_characters(self, characters: Vec<PerceivedBody>) -> Self {
+        Self { characters, ..self }
+    }
+
+    pub fn with_meals(self, meals: Vec<(u8, u8, u8)>) -> Self {
+        Self { meals, ..self }
+    }
+
+    pub fn with_letters(self, letters: Vec<char>) -> Self {
+        Self { letters, ..self }
+    }
+
+    pub fn with_touching_self(self, touching_self: bool) -> Self {
+        Self { touching_self, ..self }
+    }
+
+    pub fn with_carrying(self, carrying: Option<u128>) -> Self {
+        Self { carrying, ..self }
+    }
+
+    pub fn with_threats(self, threats: Vec<PerceivedBody>) -> Self {
+        Self { threats, ..self }
+    }
+
+    pub fn with_velocity(self, velocity: B2vec2) -> Self {
+        Self { velocity, ..self }
+    }
+
+    pub fn with_wall(self, wall: Option<B2vec2>) -> Self {
+        Self { wall, ..self }
+    }
+
+    /// bodies around the character, nearest first
+    pub fn nearby(&self) -> &[PerceivedBody] {
+        &self.nearby
+    }
+
+    /// other characters around the character, nearest first
+    pub fn characters(&self) -> &[PerceivedBody] {
+        &self.characters
+    }
+
+    /// average colors of the bodies the character has eaten since the last update
+    pub fn meals(&self) -> &[(u8, u8, u8)] {
+        &self.meals
+    }
+
+    /// letters & numbers the character has eaten since the last update
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// the character has run into one of its own segments
+    pub fn touching_self(&self) -> bool {
+        self.touching_self
+    }
+
+    /// other characters that could eat the character or that it flees from, nearest first
+    pub fn threats(&self) -> &[PerceivedBody] {
+        &self.threats
+    }
+
+    /// current velocity of the character
+    pub fn velocity(&self) -> B2vec2 {
+        self.velocity
+    }
+
+    /// offset from the character to the closest point on the nearest wall
+    pub fn wall(&self) -> Option<B2vec2> {
+        self.wall
+    }
+
+    /// id of the body the character is holding on to
+    pub fn carrying(&self) -> Option<u128> {
+        self.carrying
     }
 }
 
 pub struct CharacterFactory {
-    pac_man: CharacterLifetimeFactory,
     rng: ThreadRng,
     config: PhysicsConfig
 }
 
 impl CharacterFactory {
     pub fn new(config: PhysicsConfig) -> Self {
-        Self { pac_man: pac_man_lifetime(), rng: thread_rng(), config }
+        Self { rng: thread_rng(), config }
     }
 
-    pub fn new_character(&mut self, character: CharacterType) -> Character {
-        match character {
-            CharacterType::PacMan => {
-                let lifetime = pac_man_lifetime().new_lifetime(&mut self.rng);
-                Character::PacMan(PacManState::new(&mut self.rng, lifetime))
-            }
-        }
+    pub fn new_character(&mut self, character_type: CharacterType) -> Character {
+        let behaviour = behaviour(character_type);
+        let lifetime = behaviour.lifetime().new_lifetime(&mut self.rng);
+        Character { character_type, lifetime, mind: behaviour.spawn(&mut self.rng) }
     }
 
     pub fn shape(&mut self, character: CharacterType) -> ShapeDefPtr {
-        match character {
-            CharacterType::PacMan => pac_man_shape(self.config.polygon_scale)
-        }
+        behaviour(character).shape(self.config.polygon_scale)
     }
 
     pub fn update(&mut self, character: &mut Character, delta: Duration, world_state: CharacterWorldState) -> CharacterPhysics {
-        match character {
-            Character::PacMan(pac_man) => {
-                pac_man.update(&mut self.rng, delta, world_state);
-                CharacterPhysics::new(pac_man.angle(), pac_man.velocity(self.config.polygon_scale))
-            }
+        if !world_state.meals().is_empty() {
+            character.lifetime.attack();
+        }
+        character.lifetime.set_alone(world_state.nearby().is_empty() && world_state.characters().is_empty());
+        let state = character.lifetime.update(delta);
+        let velocity = world_state.velocity();
+        let physics = character.mind.update(&mut self.rng, delta, state, world_state, self.config.polygon_scale)
+            .blended(velocity, delta);
+        if state.is_still() {
+            physics.still()
+        } else {
+            physics
         }
     }
 }
 
 pub fn sound(config: AudioConfig) -> Result<CharacterSound, String> {
-    CharacterSound::new(config, &[pac_man_sound()])
-}
\ No newline at end of file
+    let data = registry::behaviours().map(|b| b.sound()).collect::<Vec<_>>();
+    CharacterSound::new(config, &data)
+}
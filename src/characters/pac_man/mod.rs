@@ -1,20 +1,24 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::OnceLock;
 use std::time::Duration;
 use box2d_rs::b2_math::B2vec2;
 use box2d_rs::b2_shape::ShapeDefPtr;
-use box2d_rs::shapes::b2_circle_shape::B2circleShape;
 use rand::distributions::{Distribution, Standard};
 use rand::prelude::SliceRandom;
 use rand::Rng;
 use rand::rngs::ThreadRng;
 use sdl2::render::TextureCreator;
 use sdl2::video::WindowContext;
-use crate::characters::animation::SpriteAnimationType;
-use crate::characters::{CharacterType, CharacterWorldState};
-use crate::characters::lifetime::{CharacterLifetime, CharacterLifetimeFactory, CharacterState};
+use serde::{Deserialize, Serialize};
+use crate::assets::geometry::Material;
+use crate::characters::behaviour::{CharacterBehaviour, CharacterMind};
+use crate::characters::effect::DeathEffect;
+use crate::characters::definition::{CharacterAssets, CharacterDefinition, MovementModel};
+use crate::characters::interaction::Interaction;
+use crate::characters::{Character, CharacterPhysics, CharacterType, CharacterWorldState};
+use crate::game::event::BodyKind;
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
 use crate::characters::sound::CharacterSoundData;
-use crate::characters::sprites::{CharacterSprites, SpriteSheetFormat};
+use crate::characters::sprites::CharacterSprites;
 
 const SPRITE: &[u8] = include_bytes!("sprite.png");
 const SPRITE_DEATH: &[u8] = include_bytes!("death.png");
@@ -23,48 +27,118 @@ const SOUND_CREATE: &[u8] = include_bytes!("chomp.ogg");
 const SOUND_DESTROY: &[u8] = include_bytes!("death.ogg");
 const SOUND_EAT: &[u8] = include_bytes!("eat_ghost.ogg");
 
-const PAC_MAN_RADIUS_METERS: f32 = 0.2;
-const PAC_MAN_LIFETIME: f32 = 20.0;
-const PAC_MAN_VELOCITY: f32 = 1.0;
-const TURN_PROBABILITY: f32 = 0.01;
-const FEEDING_TURN_PROBABILITY: f32 = 0.05;
-const PAC_MAN_FRAMES: usize = 3;
-const PAC_MAN_DEATH_FRAMES: usize = 12;
-
-pub fn pac_man_sprites(texture_creator: &TextureCreator<WindowContext>) -> Result<CharacterSprites, String> {
-    CharacterSprites::new(
-        texture_creator,
-        SpriteSheetFormat::exclusive_linear(SPRITE, PAC_MAN_FRAMES),
-        SpriteSheetFormat::exclusive_linear(SPRITE_DEATH, PAC_MAN_DEATH_FRAMES),
-    )
-}
+const FLEEING_TURN_PROBABILITY: f32 = 0.2;
+const POWER_SECONDS: f32 = 8.0;
 
-pub fn pac_man_lifetime() -> CharacterLifetimeFactory {
-    CharacterLifetimeFactory::new(
-        Duration::from_secs_f32(PAC_MAN_LIFETIME),
-        SpriteAnimationType::YoYo {
-            duration: Duration::from_millis(100),
-            frames: PAC_MAN_FRAMES
-        },
-        SpriteAnimationType::LinearWithPause {
-            duration: Duration::from_millis(150),
-            pause_for: Duration::from_millis(200),
-            resume_from_frame: 0,
-            frames: PAC_MAN_DEATH_FRAMES
-        },
-    )
+impl CharacterType {
+    pub const PAC_MAN: CharacterType = CharacterType::BuiltIn("PacMan");
 }
-pub fn pac_man_sound() -> CharacterSoundData {
-    CharacterSoundData::new(CharacterType::PacMan, SOUND_CREATE, SOUND_DESTROY, SOUND_EAT)
+
+pub struct PacMan;
+
+impl PacMan {
+    /// tuning for pac-man, see `pac_man.json`
+    fn definition() -> &'static (CharacterDefinition, CharacterAssets) {
+        static DEFINITION: OnceLock<(CharacterDefinition, CharacterAssets)> = OnceLock::new();
+        DEFINITION.get_or_init(|| {
+            let definition = Self::parse(include_str!("pac_man.json")).expect("bad pac-man definition");
+            let assets = definition.assets(|file| match file {
+                "sprite.png" => Ok(SPRITE),
+                "death.png" => Ok(SPRITE_DEATH),
+                "chomp.ogg" => Ok(SOUND_CREATE),
+                "death.ogg" => Ok(SOUND_DESTROY),
+                "eat_ghost.ogg" => Ok(SOUND_EAT),
+                _ => Err(format!("{} is not bundled with pac-man", file))
+            }).expect("bad pac-man definition");
+            (definition, assets)
+        })
+    }
+
+    /// a definition pac-man can be played with
+    fn parse(json: &str) -> Result<CharacterDefinition, String> {
+        let definition = CharacterDefinition::parse(json)?;
+        match definition.movement {
+            MovementModel::FourWay { .. } => Ok(definition),
+            movement => Err(format!("pac-man only moves four ways, not {:?}", movement))
+        }
+    }
+
+    /// (turn_probability, feeding_turn_probability)
+    fn turn_probabilities() -> (f32, f32) {
+        match Self::definition().0.movement {
+            MovementModel::FourWay { turn_probability, feeding_turn_probability } => (turn_probability, feeding_turn_probability),
+            _ => unreachable!("pac-man's movement is checked as it's parsed")
+        }
+    }
 }
 
-pub fn pac_man_shape(polygon_scale: f32) -> ShapeDefPtr{
-    let mut shape = B2circleShape::default();
-    shape.base.m_radius = PAC_MAN_RADIUS_METERS * polygon_scale;
-    Rc::new(RefCell::new(shape))
+impl CharacterBehaviour for PacMan {
+    fn character_type(&self) -> CharacterType {
+        CharacterType::PAC_MAN
+    }
+
+    fn weight(&self) -> f32 {
+        Self::definition().0.weight
+    }
+
+    fn material(&self) -> Material {
+        Self::definition().0.material
+    }
+
+    fn gravity_scale(&self) -> f32 {
+        Self::definition().0.gravity_scale
+    }
+
+    fn destroys_on_collision(&self) -> bool {
+        Self::definition().0.destroys_on_collision
+    }
+
+    fn interaction(&self, other: CharacterType) -> Interaction {
+        if other.is_ghost() {
+            Interaction::Eat
+        } else {
+            Interaction::Bounce
+        }
+    }
+
+    fn can_eat(&self, me: &Character, _other: &Character) -> bool {
+        me.is_powered()
+    }
+
+    fn death_effect(&self) -> DeathEffect {
+        Self::definition().0.death_effect
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory {
+        let (definition, assets) = Self::definition();
+        definition.lifetime(assets)
+    }
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        Self::definition().0.shape(polygon_scale)
+    }
+
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind> {
+        Box::new(PacManState::new(rng))
+    }
+
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String> {
+        let state: PacManState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        Ok(Box::new(state))
+    }
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        let (definition, assets) = Self::definition();
+        definition.sprites(assets, texture_creator)
+    }
+
+    fn sound(&self) -> CharacterSoundData {
+        let (definition, assets) = Self::definition();
+        definition.sound(assets, CharacterType::PAC_MAN)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PacManDirection {
     Up, Down, Left, Right
 }
@@ -79,13 +153,36 @@ impl PacManDirection {
         }
     }
 
-    pub fn velocity(&self, polygon_scale: f32) -> B2vec2 {
-        let velocity = polygon_scale * PAC_MAN_VELOCITY;
+    pub fn velocity(&self, speed: f32) -> B2vec2 {
+        match self {
+            PacManDirection::Up => B2vec2::new(0.0, -speed),
+            PacManDirection::Down => B2vec2::new(0.0, speed),
+            PacManDirection::Left => B2vec2::new(-speed, 0.0),
+            PacManDirection::Right => B2vec2::new(speed, 0.0),
+        }
+    }
+
+    pub fn clockwise(&self) -> Self {
         match self {
-            PacManDirection::Up => B2vec2::new(0.0, -velocity),
-            PacManDirection::Down => B2vec2::new(0.0, velocity),
-            PacManDirection::Left => B2vec2::new(-velocity, 0.0),
-            PacManDirection::Right => B2vec2::new(velocity, 0.0),
+            PacManDirection::Up => PacManDirection::Right,
+            PacManDirection::Right => PacManDirection::Down,
+            PacManDirection::Down => PacManDirection::Left,
+            PacManDirection::Left => PacManDirection::Up
+        }
+    }
+
+    /// the direction that best follows the offset
+    pub fn towards(offset: B2vec2) -> Self {
+        if offset.x.abs() > offset.y.abs() {
+            if offset.x < 0.0 {
+                PacManDirection::Left
+            } else {
+                PacManDirection::Right
+            }
+        } else if offset.y < 0.0 {
+            PacManDirection::Up
+        } else {
+            PacManDirection::Down
         }
     }
 
@@ -107,82 +204,118 @@ impl Distribution<PacManDirection> for Standard {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PacManState {
     direction: PacManDirection,
-    lifetime: CharacterLifetime,
-    since_last_turn: f32
+    since_last_turn: f32,
+    /// seconds of power left
+    #[serde(default)]
+    power: f32
 }
 
-impl PacManState {
-    pub fn new(rng: &mut ThreadRng, lifetime: CharacterLifetime) -> Self {
-        Self { direction: rng.gen(), lifetime, since_last_turn: 0.0 }
-    }
+impl CharacterMind for PacManState {
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics {
+        if !state.is_alive() {
+            return CharacterPhysics::new(0.0, B2vec2::zero());
+        }
 
-    pub fn velocity(&self, polygon_scale: f32) -> B2vec2 {
-        match self.lifetime.state() {
-            CharacterState::Alive => self.direction.velocity(polygon_scale),
-            CharacterState::Dead | CharacterState::Death => B2vec2::zero(),
+        self.power = (self.power - delta.as_secs_f32()).max(0.0);
+        let powers_up = world_state.letters().iter().any(|&c| is_power_pellet(c));
+        if powers_up {
+            self.power = POWER_SECONDS;
         }
-    }
 
-    pub fn angle(&self) -> f32 {
-        match self.lifetime.state() {
-            CharacterState::Alive => self.direction.angle(),
-            CharacterState::Dead | CharacterState::Death => 0.0
+        let (turn_probability, feeding_turn_probability) = PacMan::turn_probabilities();
+        self.since_last_turn += delta.as_secs_f32();
+        if world_state.is_colliding {
+            self.direction = Self::preferred_direction(&world_state).or_else(||
+                self.direction.other_directions().choose(rng).copied()
+            ).unwrap();
+            self.since_last_turn = 0.0;
+        } else if let Some(threat) = world_state.threats().iter().find(|body| body.visible) {
+            // run away from anything that could eat pac-man
+            let away = PacManDirection::towards(B2vec2::zero() - threat.offset);
+            if self.direction != away && self.should_turn(rng, FLEEING_TURN_PROBABILITY) {
+                self.direction = away;
+                self.since_last_turn = 0.0;
+            }
+        } else if let Some(preferred_direction) = Self::preferred_direction(&world_state) {
+            // behaviour when feeding, prefer turning to direction of closest body with slight debounce
+            if self.direction != preferred_direction && self.should_turn(rng, feeding_turn_probability) {
+                self.direction = preferred_direction;
+                self.since_last_turn = 0.0;
+            }
+        // behaviour when nothing left to eat: slow, random turns
+        } else if self.should_turn(rng, turn_probability) {
+            self.direction = rng.gen();
+            self.since_last_turn = 0.0;
+        }
+        let speed = polygon_scale * PacMan::definition().0.speed;
+        let physics = CharacterPhysics::new(self.direction.angle(), self.direction.velocity(speed));
+        if powers_up {
+            physics.with_power_up()
+        } else {
+            physics
         }
     }
 
-    pub fn lifetime(&self) -> &CharacterLifetime {
-        &self.lifetime
+    fn is_powered(&self) -> bool {
+        self.power > 0.0
     }
 
-    pub fn lifetime_mut(&mut self) -> &mut CharacterLifetime {
-        &mut self.lifetime
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
     }
 
-    pub fn update(&mut self, rng: &mut ThreadRng, delta: Duration, world_state: CharacterWorldState) -> CharacterState {
-        let state = self.lifetime.update(delta);
-        if state.is_alive() {
-            self.since_last_turn += delta.as_secs_f32();
-            if world_state.is_colliding {
-                self.direction = Self::preferred_direction(world_state).or_else(||
-                    self.direction.other_directions().choose(rng).copied()
-                ).unwrap();
-                self.since_last_turn = 0.0;
-            } else if let Some(preferred_direction) = Self::preferred_direction(world_state) {
-                // behaviour when feeding, prefer turning to direction of closest body with slight debounce
-                if self.direction != preferred_direction && self.should_turn(rng, FEEDING_TURN_PROBABILITY) {
-                    self.direction = preferred_direction;
-                    self.since_last_turn = 0.0;
-                }
-            // behaviour when nothing left to eat: slow, random turns
-            } else if self.should_turn(rng, TURN_PROBABILITY) {
-                self.direction = rng.gen();
-                self.since_last_turn = 0.0;
-            }
-        }
-        state
+    fn clone_mind(&self) -> Box<dyn CharacterMind> {
+        Box::new(self.clone())
+    }
+}
+
+impl PacManState {
+    pub fn new(rng: &mut ThreadRng) -> Self {
+        Self { direction: rng.gen(), since_last_turn: 0.0, power: 0.0 }
     }
 
     fn should_turn(&self, rng: &mut ThreadRng, probability: f32) -> bool {
         (self.since_last_turn * probability) > rng.gen::<f32>()
     }
 
-    fn preferred_direction(world_state: CharacterWorldState) -> Option<PacManDirection> {
-        world_state.closest_body.map(|d| {
-            if d.x.abs() > d.y.abs() {
-                // farthest away in the x direction
-                if d.x > 0.0 {
-                    PacManDirection::Left
-                } else {
-                    PacManDirection::Right
-                }
-            } else if d.y > 0.0 {
-                PacManDirection::Up
-            } else {
-                PacManDirection::Down
-            }
-        })
+    fn preferred_direction(world_state: &CharacterWorldState) -> Option<PacManDirection> {
+        // head for food in plain sight before the closest food behind something
+        let visible_food = world_state.nearby().iter()
+            .find(|body| body.visible && body.kind == BodyKind::Alphanumeric)
+            .map(|body| body.offset);
+        // closest body points from the food to pac-man
+        let closest_food = world_state.closest_body.map(|d| B2vec2::zero() - d);
+        visible_food.or(closest_food).map(PacManDirection::towards)
+    }
+}
+
+/// the round letters & numbers are pac-man's power pellets
+fn is_power_pellet(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), 'O' | '0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pac_man_only_moves_four_ways() {
+        let json = include_str!("pac_man.json");
+        assert!(PacMan::parse(json).is_ok());
+        let steering = json.replace(
+            r#"{ "FourWay": { "turn_probability": 0.01, "feeding_turn_probability": 0.05 } }"#,
+            r#"{ "Steering": { "turn_rate": 90.0 } }"#
+        );
+        assert!(PacMan::parse(&steering).is_err());
     }
 }
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use crate::characters::behaviour::CharacterBehaviour;
+use crate::characters::{CharacterType, CustomCharacter};
+use crate::characters::custom::CustomBehaviour;
+use crate::characters::definition::{CharacterAssets, CharacterDefinition};
+use crate::characters::ghost::{BLINKY, CLYDE, INKY, PINKY};
+use crate::characters::pac_man::PacMan;
+use crate::characters::snake::Snake;
+use crate::characters::solar_jetman::Jetman;
+
+/// characters built into the game, a new one declares its own `CharacterType` & behaviour in its module
+/// & only needs listing here, `every_character_is_registered_once` catches two with the same name
+static BEHAVIOURS: &[&dyn CharacterBehaviour] = &[
+    &PacMan,
+    &BLINKY,
+    &PINKY,
+    &INKY,
+    &CLYDE,
+    &Snake,
+    &Jetman
+];
+
+/// characters loaded from definition files
+static CUSTOM: OnceLock<Vec<CustomBehaviour>> = OnceLock::new();
+
+/// adds characters loaded from definition files, once before the game starts
+pub fn register(definitions: Vec<(CharacterDefinition, CharacterAssets)>) -> Result<(), String> {
+    let mut custom: Vec<CustomBehaviour> = vec![];
+    for (definition, assets) in definitions {
+        if by_name(&definition.name).is_some() || custom.iter().any(|b| b.name() == definition.name) {
+            println!("Character {} is defined more than once, skipping", definition.name);
+            continue;
+        }
+        let character_type = CharacterType::Custom(CustomCharacter::new(custom.len() as u16));
+        custom.push(CustomBehaviour::new(character_type, definition, assets));
+    }
+    CUSTOM.set(custom).map_err(|_| "characters are already registered".to_string())
+}
+
+pub fn behaviours() -> impl Iterator<Item = &'static dyn CharacterBehaviour> {
+    let custom = CUSTOM.get().into_iter().flatten().map(|b| b as &dyn CharacterBehaviour);
+    BEHAVIOURS.iter().copied().chain(custom)
+}
+
+pub fn by_name(name: &str) -> Option<CharacterType> {
+    behaviours().find(|b| b.name() == name).map(|b| b.character_type())
+}
+
+pub fn behaviour(character: CharacterType) -> &'static dyn CharacterBehaviour {
+    behaviours()
+        .find(|b| b.character_type() == character)
+        .unwrap_or_else(|| panic!("character {:?} is not registered", character))
+}
+
+/// Picks random characters in proportion to their weights
+pub struct CharacterWeights {
+    characters: Vec<CharacterType>,
+    index: Option<WeightedIndex<f32>>
+}
+
+impl CharacterWeights {
+    /// characters missing from the overrides keep their default weight
+    pub fn new(overrides: &HashMap<CharacterType, f32>) -> Self {
+        let (characters, weights): (Vec<CharacterType>, Vec<f32>) = behaviours()
+            .map(|b| {
+                let character = b.character_type();
+                (character, overrides.get(&character).copied().unwrap_or(b.weight()).max(0.0))
+            })
+            .unzip();
+        // all zero weights fall back to picking evenly
+        let index = WeightedIndex::new(&weights).ok();
+        Self { characters, index }
+    }
+}
+
+impl Distribution<CharacterType> for CharacterWeights {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> CharacterType {
+        match &self.index {
+            Some(index) => self.characters[index.sample(rng)],
+            None => self.characters[rng.gen_range(0..self.characters.len())]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use super::*;
+
+    #[test]
+    fn every_character_is_registered_once() {
+        for behaviour in behaviours() {
+            assert_eq!(behaviours().filter(|b| b.name() == behaviour.name()).count(), 1, "{}", behaviour.name());
+            assert_eq!(by_name(&behaviour.name()), Some(behaviour.character_type()));
+        }
+    }
+
+    #[test]
+    fn characters_are_saved_by_name() {
+        let json = serde_json::to_string(&CharacterType::PAC_MAN).unwrap();
+        assert_eq!(json, "\"PacMan\"");
+        assert_eq!(serde_json::from_str::<CharacterType>(&json).unwrap(), CharacterType::PAC_MAN);
+        assert!(serde_json::from_str::<CharacterType>("\"Nobody\"").is_err());
+    }
+
+    #[test]
+    fn all_zero_weights_still_pick_a_character() {
+        let overrides = behaviours().map(|b| (b.character_type(), 0.0)).collect();
+        let weights = CharacterWeights::new(&overrides);
+        let character = thread_rng().sample(&weights);
+        assert!(behaviours().any(|b| b.character_type() == character));
+    }
+}
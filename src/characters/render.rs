@@ -1,30 +1,48 @@
+use std::collections::HashMap;
 use sdl2::rect::Rect;
 use sdl2::render::{TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
-use crate::characters::Character;
+use crate::characters::{Character, CharacterType, registry};
+use crate::characters::animation::WALK;
 use crate::characters::sprites::CharacterSprites;
-use crate::characters::pac_man::pac_man_sprites;
+use crate::animate::tween::Look;
 
 pub struct CharacterRender<'a> {
-    pac_man: CharacterSprites<'a>
+    sprites: HashMap<CharacterType, CharacterSprites<'a>>
 }
 
 impl<'a> CharacterRender<'a> {
-    pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> Result<Self, String> {
-        Ok(Self { pac_man: pac_man_sprites(texture_creator)? })
-    }
-
-    pub fn draw_character(&self, canvas: &mut WindowCanvas, character: Character, aabb: Rect, angle: f64) -> Result<(), String> {
-        match character {
-            Character::PacMan(pac_man) => {
-                let (state, frame) = pac_man.lifetime().animation_frame();
-                let sprites = if state.is_alive() {
-                    self.pac_man.sprites()
-                } else {
-                    self.pac_man.death_sprites()
-                };
-                sprites.draw_frame(canvas, aabb, frame, angle)
+    /// characters with sprites that can't be loaded e.g. a corrupt png in a definition file are skipped
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> Self {
+        let mut sprites = HashMap::new();
+        for behaviour in registry::behaviours() {
+            match behaviour.sprites(texture_creator) {
+                Ok(character_sprites) => {
+                    sprites.insert(behaviour.character_type(), character_sprites);
+                }
+                Err(error) => println!("Cannot load the sprites of {}, {}, skipping", behaviour.name(), error)
             }
         }
+        Self { sprites }
+    }
+
+    pub fn can_draw(&self, character: CharacterType) -> bool {
+        self.sprites.contains_key(&character)
     }
-}
\ No newline at end of file
+
+    pub fn draw_character(&mut self, canvas: &mut WindowCanvas, character: &Character, aabb: Rect, angle: f64, look: Look) -> Result<(), String> {
+        let character_sprites = match self.sprites.get_mut(&character.character_type()) {
+            Some(sprites) => sprites,
+            None => return Ok(())
+        };
+        let (name, frame) = character.lifetime().animation_frame();
+        let name = if character_sprites.animation(name).is_some() { name } else { WALK };
+        let sprites = match character_sprites.animation(name) {
+            Some(sprites) => sprites,
+            None => return Ok(())
+        };
+        // a saved game could be playing an animation with a different number of frames
+        let frame = frame.min(sprites.frame_count() - 1);
+        sprites.draw_frame(canvas, aabb, frame, angle, look)
+    }
+}
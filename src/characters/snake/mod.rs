@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use box2d_rs::shapes::b2_circle_shape::B2circleShape;
+use rand::Rng;
+use rand::rngs::ThreadRng;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use crate::characters::animation::SpriteAnimationType;
+use crate::characters::behaviour::{CharacterBehaviour, CharacterMind};
+use crate::characters::{CharacterPhysics, CharacterType, CharacterWorldState};
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::{CharacterSprites, SpriteSheetFormat};
+use crate::game::event::BodyKind;
+
+const SPRITE: &[u8] = include_bytes!("sprite.png");
+const SPRITE_DEATH: &[u8] = include_bytes!("death.png");
+const SOUND_EAT: &[u8] = include_bytes!("../pac_man/chomp.ogg");
+
+const SNAKE_RADIUS_METERS: f32 = 0.15;
+const SNAKE_LIFETIME: f32 = 40.0;
+const SNAKE_VELOCITY: f32 = 0.8;
+const SNAKE_FRAMES: usize = 2;
+const SNAKE_DEATH_FRAMES: usize = 4;
+/// degrees per second the snake can turn towards food
+const TURN_RATE: f32 = 180.0;
+/// degrees per second the snake meanders when there is nothing to eat
+const WANDER_RATE: f32 = 60.0;
+
+impl CharacterType {
+    pub const SNAKE: CharacterType = CharacterType::BuiltIn("Snake");
+}
+
+pub struct Snake;
+
+impl CharacterBehaviour for Snake {
+    fn character_type(&self) -> CharacterType {
+        CharacterType::SNAKE
+    }
+
+    fn weight(&self) -> f32 {
+        0.5
+    }
+
+    fn destroys_on_collision(&self) -> bool {
+        true
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory {
+        CharacterLifetimeFactory::new(
+            Duration::from_secs_f32(SNAKE_LIFETIME),
+            SpriteAnimationType::LinearWithPause {
+                duration: Duration::from_millis(150),
+                pause_for: Duration::from_millis(1000),
+                resume_from_frame: 0,
+                frames: SNAKE_FRAMES
+            },
+            SpriteAnimationType::Linear {
+                duration: Duration::from_millis(200),
+                frames: SNAKE_DEATH_FRAMES
+            },
+        )
+    }
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        let mut shape = B2circleShape::default();
+        shape.base.m_radius = SNAKE_RADIUS_METERS * polygon_scale;
+        Rc::new(RefCell::new(shape))
+    }
+
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind> {
+        Box::new(SnakeState { heading: rng.gen_range(0.0..360.0) })
+    }
+
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String> {
+        let state: SnakeState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        Ok(Box::new(state))
+    }
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        CharacterSprites::new(
+            texture_creator,
+            SpriteSheetFormat::exclusive_linear(SPRITE, SNAKE_FRAMES),
+            SpriteSheetFormat::exclusive_linear(SPRITE_DEATH, SNAKE_DEATH_FRAMES),
+        )
+    }
+
+    fn sound(&self) -> CharacterSoundData {
+        CharacterSoundData::silent(CharacterType::SNAKE).with_attack(SOUND_EAT)
+    }
+}
+
+/// The head of the snake, the rest of it is made of segments that follow the head around
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnakeState {
+    /// degrees
+    heading: f32
+}
+
+impl CharacterMind for SnakeState {
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics {
+        if !state.is_alive() {
+            return CharacterPhysics::new(self.heading, B2vec2::zero());
+        }
+        if world_state.touching_self() {
+            return CharacterPhysics::new(self.heading, B2vec2::zero()).with_death();
+        }
+
+        let delta = delta.as_secs_f32();
+        if world_state.is_colliding {
+            // turn away from whatever is in the way
+            self.heading += rng.gen_range(90.0..270.0);
+        } else if let Some(food) = Self::food(&world_state) {
+            let towards = food.y.atan2(food.x).to_degrees();
+            let turn = (towards - self.heading + 540.0) % 360.0 - 180.0;
+            self.heading += turn.clamp(-TURN_RATE * delta, TURN_RATE * delta);
+        } else {
+            self.heading += rng.gen_range(-1.0..1.0) * WANDER_RATE * delta;
+        }
+        self.heading = self.heading.rem_euclid(360.0);
+
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        let speed = SNAKE_VELOCITY * polygon_scale;
+        CharacterPhysics::new(self.heading, B2vec2::new(cos * speed, sin * speed))
+            .with_growth(world_state.meals().to_vec())
+    }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn clone_mind(&self) -> Box<dyn CharacterMind> {
+        Box::new(self.clone())
+    }
+}
+
+impl SnakeState {
+    /// offset from the head to the nearest letter
+    fn food(world_state: &CharacterWorldState) -> Option<B2vec2> {
+        let visible_food = world_state.nearby().iter()
+            .find(|body| body.visible && body.kind == BodyKind::Alphanumeric)
+            .map(|body| body.offset);
+        // closest body points from the food to the snake
+        visible_food.or(world_state.closest_body.map(|d| B2vec2::zero() - d))
+    }
+}
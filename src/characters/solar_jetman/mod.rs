@@ -0,0 +1,214 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use box2d_rs::b2_shape::ShapeDefPtr;
+use box2d_rs::shapes::b2_circle_shape::B2circleShape;
+use rand::Rng;
+use rand::rngs::ThreadRng;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use crate::characters::animation::SpriteAnimationType;
+use crate::characters::behaviour::{CharacterBehaviour, CharacterMind};
+use crate::characters::interaction::Interaction;
+use crate::characters::{CharacterPhysics, CharacterType, CharacterWorldState, PerceivedBody};
+use crate::characters::lifetime::{CharacterLifetimeFactory, CharacterState};
+use crate::characters::sound::CharacterSoundData;
+use crate::characters::sprites::{CharacterSprites, SpriteSheetFormat};
+use crate::game::event::BodyKind;
+
+const SPRITE: &[u8] = include_bytes!("jetman.png");
+const SPRITE_DEATH: &[u8] = include_bytes!("exhaust.png");
+
+const JETMAN_RADIUS_METERS: f32 = 0.2;
+const JETMAN_LIFETIME: f32 = 30.0;
+const JETMAN_VELOCITY: f32 = 1.5;
+const JETMAN_FRAMES: usize = 30;
+/// runs out of fuel in a puff of exhaust
+const JETMAN_DEATH_FRAMES: usize = 3;
+/// degrees per second when flying in an arc
+const MAX_TURN_RATE: f32 = 90.0;
+/// how long each arc lasts before curving the other way
+const ARC_SECONDS: f32 = 2.0;
+/// a letter this close can be picked up
+const REACH_METERS: f32 = 0.6;
+/// how long a letter is carried before it is dropped
+const MIN_CARRY_SECONDS: f32 = 2.0;
+const MAX_CARRY_SECONDS: f32 = 5.0;
+/// stops jetman picking the same letter straight back up
+const DROP_COOLDOWN_SECONDS: f32 = 2.0;
+
+impl CharacterType {
+    pub const JETMAN: CharacterType = CharacterType::BuiltIn("Jetman");
+}
+
+pub struct Jetman;
+
+impl CharacterBehaviour for Jetman {
+    fn character_type(&self) -> CharacterType {
+        CharacterType::JETMAN
+    }
+
+    fn weight(&self) -> f32 {
+        0.5
+    }
+
+    fn gravity_scale(&self) -> f32 {
+        0.0
+    }
+
+    fn interaction(&self, other: CharacterType) -> Interaction {
+        if other.is_ghost() {
+            Interaction::Flee
+        } else {
+            Interaction::Bounce
+        }
+    }
+
+    fn lifetime(&self) -> CharacterLifetimeFactory {
+        CharacterLifetimeFactory::new(
+            Duration::from_secs_f32(JETMAN_LIFETIME),
+            SpriteAnimationType::Linear {
+                duration: Duration::from_millis(50),
+                frames: JETMAN_FRAMES
+            },
+            SpriteAnimationType::Linear {
+                duration: Duration::from_millis(200),
+                frames: JETMAN_DEATH_FRAMES
+            },
+        )
+    }
+
+    fn shape(&self, polygon_scale: f32) -> ShapeDefPtr {
+        let mut shape = B2circleShape::default();
+        shape.base.m_radius = JETMAN_RADIUS_METERS * polygon_scale;
+        Rc::new(RefCell::new(shape))
+    }
+
+    fn spawn(&self, rng: &mut ThreadRng) -> Box<dyn CharacterMind> {
+        Box::new(JetmanState::new(rng))
+    }
+
+    fn load(&self, state: serde_json::Value) -> Result<Box<dyn CharacterMind>, String> {
+        let state: JetmanState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        Ok(Box::new(state))
+    }
+
+    fn sprites<'a>(&self, texture_creator: &'a TextureCreator<WindowContext>) -> Result<CharacterSprites<'a>, String> {
+        CharacterSprites::new(
+            texture_creator,
+            SpriteSheetFormat::exclusive_linear(SPRITE, JETMAN_FRAMES),
+            SpriteSheetFormat::exclusive_linear(SPRITE_DEATH, JETMAN_DEATH_FRAMES),
+        )
+    }
+
+    fn sound(&self) -> CharacterSoundData {
+        CharacterSoundData::silent(CharacterType::JETMAN)
+    }
+}
+
+/// Flies about in arcs, picking up letters & dropping them somewhere else
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JetmanState {
+    /// degrees
+    heading: f32,
+    /// degrees per second, the sign is which way the arc curves
+    turn_rate: f32,
+    since_last_arc: f32,
+    /// seconds left until the carried letter is dropped
+    carry_for: f32,
+    /// seconds left until another letter can be picked up
+    cooldown: f32
+}
+
+impl CharacterMind for JetmanState {
+    fn update(
+        &mut self,
+        rng: &mut ThreadRng,
+        delta: Duration,
+        state: CharacterState,
+        world_state: CharacterWorldState,
+        polygon_scale: f32
+    ) -> CharacterPhysics {
+        if !state.is_alive() {
+            return CharacterPhysics::new(0.0, B2vec2::zero()).with_release();
+        }
+
+        let delta = delta.as_secs_f32();
+        self.since_last_arc += delta;
+        self.cooldown -= delta;
+        if self.since_last_arc >= ARC_SECONDS {
+            self.turn_rate = Self::random_turn_rate(rng);
+            self.since_last_arc = 0.0;
+        }
+
+        if world_state.is_colliding {
+            // bounce back the way we came
+            self.heading += 180.0;
+            self.turn_rate = -self.turn_rate;
+        } else if let Some(threat) = world_state.threats().iter().find(|body| body.visible) {
+            // curve away from the threat as fast as possible
+            let away = threat.offset.y.atan2(threat.offset.x).to_degrees() + 180.0;
+            let turn = (away - self.heading + 540.0) % 360.0 - 180.0;
+            self.turn_rate = turn.signum() * MAX_TURN_RATE;
+        } else if let (None, Some(letter)) = (world_state.carrying(), Self::nearest_letter(&world_state)) {
+            // curve towards the letter
+            let towards = letter.offset.y.atan2(letter.offset.x).to_degrees();
+            let turn = (towards - self.heading + 540.0) % 360.0 - 180.0;
+            self.turn_rate = turn.signum() * self.turn_rate.abs();
+        }
+        self.heading = (self.heading + self.turn_rate * delta).rem_euclid(360.0);
+
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        let speed = JETMAN_VELOCITY * polygon_scale;
+        // jetman stays upright whichever way he flies
+        let physics = CharacterPhysics::new(0.0, B2vec2::new(cos * speed, sin * speed));
+
+        if world_state.carrying().is_some() {
+            self.carry_for -= delta;
+            if self.carry_for <= 0.0 {
+                self.cooldown = DROP_COOLDOWN_SECONDS;
+                return physics.with_release();
+            }
+            return physics;
+        }
+
+        match Self::nearest_letter(&world_state) {
+            Some(letter) if self.cooldown <= 0.0 && letter.distance <= REACH_METERS => {
+                self.carry_for = rng.gen_range(MIN_CARRY_SECONDS..MAX_CARRY_SECONDS);
+                physics.with_grab(letter.id)
+            }
+            _ => physics
+        }
+    }
+
+    fn save(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn clone_mind(&self) -> Box<dyn CharacterMind> {
+        Box::new(self.clone())
+    }
+}
+
+impl JetmanState {
+    pub fn new(rng: &mut ThreadRng) -> Self {
+        Self {
+            heading: rng.gen_range(0.0..360.0),
+            turn_rate: Self::random_turn_rate(rng),
+            since_last_arc: 0.0,
+            carry_for: 0.0,
+            cooldown: 0.0
+        }
+    }
+
+    fn random_turn_rate(rng: &mut ThreadRng) -> f32 {
+        rng.gen_range(-MAX_TURN_RATE..MAX_TURN_RATE)
+    }
+
+    fn nearest_letter(world_state: &CharacterWorldState) -> Option<&PerceivedBody> {
+        world_state.nearby().iter()
+            .find(|body| body.visible && body.kind == BodyKind::Alphanumeric)
+    }
+}
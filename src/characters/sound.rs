@@ -4,25 +4,62 @@ use std::rc::Rc;
 use sdl2::mixer::{Channel, Chunk, Music, set_channel_finished};
 use crate::assets::sound::LoadSound;
 use crate::assets::sound::playable::Playable;
-use crate::characters::CharacterType;
+use crate::characters::{CharacterType, registry};
+use crate::characters::lifetime::CharacterState;
 use crate::config::AudioConfig;
 
 #[derive(Debug, Clone)]
 pub struct CharacterSoundData {
     character: CharacterType,
-    create: &'static [u8],
-    destroy: &'static [u8],
-    attack: &'static [u8],
+    create: Option<&'static [u8]>,
+    destroy: Option<&'static [u8]>,
+    attack: Option<&'static [u8]>,
+    /// played as the character eats another character
+    eat: Option<&'static [u8]>,
+    power_up: Option<&'static [u8]>,
+    /// played as the character enters each state
+    states: Vec<(CharacterState, &'static [u8])>,
 }
 
 impl CharacterSoundData {
     pub fn new(character: CharacterType, create: &'static [u8], destroy: &'static [u8], attack: &'static [u8]) -> Self {
-        Self { character, create, destroy, attack }
+        Self { character, create: Some(create), destroy: Some(destroy), attack: Some(attack), eat: None, power_up: None, states: vec![] }
+    }
+
+    pub fn optional(
+        character: CharacterType,
+        create: Option<&'static [u8]>,
+        destroy: Option<&'static [u8]>,
+        attack: Option<&'static [u8]>
+    ) -> Self {
+        Self { character, create, destroy, attack, eat: None, power_up: None, states: vec![] }
+    }
+
+    /// a character that makes no noise
+    pub fn silent(character: CharacterType) -> Self {
+        Self { character, create: None, destroy: None, attack: None, eat: None, power_up: None, states: vec![] }
+    }
+
+    pub fn with_attack(self, attack: &'static [u8]) -> Self {
+        Self { attack: Some(attack), ..self }
+    }
+
+    pub fn with_eat(self, eat: &'static [u8]) -> Self {
+        Self { eat: Some(eat), ..self }
+    }
+
+    pub fn with_power_up(self, power_up: &'static [u8]) -> Self {
+        Self { power_up: Some(power_up), ..self }
+    }
+
+    pub fn with_state(mut self, state: CharacterState, sample: &'static [u8]) -> Self {
+        self.states.push((state, sample));
+        self
     }
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
-enum CharacterSoundType { Create, Destroy, Attack }
+enum CharacterSoundType { Create, Destroy, Attack, Eat, PowerUp, State(CharacterState) }
 
 struct CharacterSoundEntry {
     sample: Chunk,
@@ -39,26 +76,21 @@ impl CharacterSoundEntry {
 
 static mut SOUND_MAP: Option<HashMap<i32, Rc<RefCell<CharacterSoundEntry>>>> = None;
 
+type CharacterSoundMap = HashMap<(CharacterType, CharacterSoundType), Rc<RefCell<CharacterSoundEntry>>>;
+
 pub struct CharacterSound {
-    sound: HashMap<(CharacterType, CharacterSoundType), Rc<RefCell<CharacterSoundEntry>>>
+    sound: CharacterSoundMap
 }
 
 impl CharacterSound {
+    /// characters with sounds that can't be loaded e.g. a corrupt ogg in a definition file are silent
     pub fn new(config: AudioConfig, data: &[CharacterSoundData]) -> Result<Self, String> {
         let mut sound = HashMap::new();
         for data in data {
-            sound.insert(
-                (data.character, CharacterSoundType::Create),
-                CharacterSoundEntry::new(&config, data.create)?
-            );
-            sound.insert(
-                (data.character, CharacterSoundType::Destroy),
-                CharacterSoundEntry::new(&config, data.destroy)?
-            );
-            sound.insert(
-                (data.character, CharacterSoundType::Attack),
-                CharacterSoundEntry::new(&config, data.attack)?
-            );
+            match Self::entries(&config, data) {
+                Ok(entries) => sound.extend(entries),
+                Err(error) => println!("Cannot load the sounds of {}, {}, skipping", registry::behaviour(data.character).name(), error)
+            }
         }
         unsafe {
             SOUND_MAP = Some(HashMap::new());
@@ -67,6 +99,27 @@ impl CharacterSound {
         Ok(Self { sound })
     }
 
+    fn entries(
+        config: &AudioConfig,
+        data: &CharacterSoundData
+    ) -> Result<CharacterSoundMap, String> {
+        let mut entries = HashMap::new();
+        let samples = [
+            (CharacterSoundType::Create, data.create),
+            (CharacterSoundType::Destroy, data.destroy),
+            (CharacterSoundType::Attack, data.attack),
+            (CharacterSoundType::Eat, data.eat),
+            (CharacterSoundType::PowerUp, data.power_up)
+        ];
+        let states = data.states.iter().map(|&(state, sample)| (CharacterSoundType::State(state), Some(sample)));
+        for (sound_type, sample) in samples.into_iter().chain(states) {
+            if let Some(sample) = sample {
+                entries.insert((data.character, sound_type), CharacterSoundEntry::new(config, sample)?);
+            }
+        }
+        Ok(entries)
+    }
+
     fn play(&mut self, character: CharacterType, sound_type: CharacterSoundType) -> Result<(), String> {
         if let Some(sound_ptr) = self.sound.get_mut(&(character, sound_type)) {
             sound_ptr.borrow_mut().sample.play().map(|_| ())
@@ -109,6 +162,18 @@ impl CharacterSound {
         self.play_debounced(character, CharacterSoundType::Attack)
     }
 
+    pub fn play_eat(&mut self, character: CharacterType) -> Result<(), String> {
+        self.play_debounced(character, CharacterSoundType::Eat)
+    }
+
+    pub fn play_power_up(&mut self, character: CharacterType) -> Result<(), String> {
+        self.play(character, CharacterSoundType::PowerUp)
+    }
+
+    pub fn play_state(&mut self, character: CharacterType, state: CharacterState) -> Result<(), String> {
+        self.play_debounced(character, CharacterSoundType::State(state))
+    }
+
     fn channel_callback(channel: Channel) {
         unsafe {
             if let Some(sound_map) = SOUND_MAP.as_mut() {
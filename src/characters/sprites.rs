@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use sdl2::rect::{Rect};
 use sdl2::render::{Texture, TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
+use crate::characters::animation::{DIE, WALK};
 use crate::texture::{TextureFactory, TextureQuery};
+use crate::animate::tween::Look;
 
 #[derive(Debug, Clone)]
 enum FrameFormat {
@@ -13,6 +16,13 @@ enum FrameFormat {
     /// i.e. the width of a frame is texture width / frames
     ///      & height is same as texture height
     ExclusiveLinear { count: usize },
+
+    /// texture is a grid of equally sized frames, read left to right then top to bottom,
+    /// the last row can be partly empty
+    Grid { columns: usize, rows: usize, count: usize },
+
+    /// frames anywhere on the texture, see `atlas::SpriteAtlas`
+    Atlas { frames: Vec<Rect> },
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +47,22 @@ impl SpriteSheetFormat {
         }
     }
 
+    pub fn grid(file: &'static [u8], columns: usize, rows: usize, frames: usize) -> Self {
+        assert!(frames > 0 && frames <= columns * rows);
+        Self {
+            file,
+            format: FrameFormat::Grid { columns, rows, count: frames },
+        }
+    }
+
+    pub fn atlas(file: &'static [u8], frames: Vec<Rect>) -> Self {
+        assert!(!frames.is_empty());
+        Self {
+            file,
+            format: FrameFormat::Atlas { frames },
+        }
+    }
+
     pub fn sprite_sheet<'a>(
         &self,
         texture_creator: &'a TextureCreator<WindowContext>,
@@ -44,8 +70,9 @@ impl SpriteSheetFormat {
         let texture = texture_creator.load_texture_bytes_blended(self.file)?;
         let (texture_width, texture_height) = texture.size();
 
-        let frames = match self.format {
+        let frames = match &self.format {
             FrameFormat::ExclusiveLinear { count } => {
+                let count = *count;
                 let frame_width = texture_width / count as u32;
                 (0..count as u32)
                     .map(|i| Rect::new((i * frame_width) as i32, 0, frame_width, texture_height))
@@ -58,6 +85,18 @@ impl SpriteSheetFormat {
                     .map(|i| Rect::new((i * frame_size) as i32, 0, frame_size, frame_size))
                     .collect()
             }
+            FrameFormat::Grid { columns, rows, count } => {
+                let (columns, rows, count) = (*columns as u32, *rows as u32, *count as u32);
+                let frame_width = texture_width / columns;
+                let frame_height = texture_height / rows;
+                (0..count)
+                    .map(|i| {
+                        let (column, row) = (i % columns, i / columns);
+                        Rect::new((column * frame_width) as i32, (row * frame_height) as i32, frame_width, frame_height)
+                    })
+                    .collect()
+            }
+            FrameFormat::Atlas { frames } => frames.clone(),
         };
 
         Ok(SpriteSheet::new(texture, frames))
@@ -91,13 +130,18 @@ impl<'a> SpriteSheet<'a> {
     }
 
     pub fn draw_frame<A : Into<Option<f64>>>(
-        &self,
+        &mut self,
         canvas: &mut WindowCanvas,
         dest: Rect,
         frame: usize,
         angle: A,
+        look: Look,
     ) -> Result<(), String> {
         let snip = self.frames[frame];
+        let (r, g, b) = look.color_mod();
+        self.texture.set_color_mod(r, g, b);
+        self.texture.set_alpha_mod(look.alpha_mod());
+        let dest = look.scale_rect(dest);
         if let Some(angle) = angle.into() {
             canvas.copy_ex(&self.texture, snip, dest, angle, None, false, false)
         } else {
@@ -107,9 +151,9 @@ impl<'a> SpriteSheet<'a> {
 
 }
 
+/// Sprite sheets for each of a character's animations by name, see `animation::WALK` etc
 pub struct CharacterSprites<'a> {
-    sprites: SpriteSheet<'a>,
-    death_sprites: SpriteSheet<'a>,
+    animations: HashMap<String, SpriteSheet<'a>>,
 }
 
 impl<'a> CharacterSprites<'a> {
@@ -118,17 +162,23 @@ impl<'a> CharacterSprites<'a> {
         sprites: SpriteSheetFormat,
         death_sprites: SpriteSheetFormat,
     ) -> Result<Self, String> {
-        Ok(Self {
-            sprites: sprites.sprite_sheet(texture_creator)?,
-            death_sprites: death_sprites.sprite_sheet(texture_creator)?,
-        })
+        let mut animations = HashMap::new();
+        animations.insert(WALK.to_string(), sprites.sprite_sheet(texture_creator)?);
+        animations.insert(DIE.to_string(), death_sprites.sprite_sheet(texture_creator)?);
+        Ok(Self { animations })
     }
 
-    pub fn sprites(&self) -> &SpriteSheet<'a> {
-        &self.sprites
+    pub fn with_animation(
+        mut self,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        name: &str,
+        sprites: SpriteSheetFormat,
+    ) -> Result<Self, String> {
+        self.animations.insert(name.to_string(), sprites.sprite_sheet(texture_creator)?);
+        Ok(self)
     }
 
-    pub fn death_sprites(&self) -> &SpriteSheet<'a> {
-        &self.death_sprites
+    pub fn animation(&mut self, name: &str) -> Option<&mut SpriteSheet<'a>> {
+        self.animations.get_mut(name)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,195 @@
+use box2d_rs::b2_math::B2vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::characters::PerceivedBody;
+
+// Smooth movement for characters that are not stuck to four directions.
+// Each behaviour returns a desired velocity, combine them with `combine` & return the result
+// from `CharacterPhysics::with_steering` so it is blended with the current velocity.
+
+/// straight at the target at full speed
+pub fn seek(offset: B2vec2, max_speed: f32) -> B2vec2 {
+    with_length(offset, max_speed)
+}
+
+/// straight away from the target at full speed
+pub fn flee(offset: B2vec2, max_speed: f32) -> B2vec2 {
+    with_length(B2vec2::zero() - offset, max_speed)
+}
+
+/// towards the target, slowing down to stop on it once within the slowing radius
+pub fn arrive(offset: B2vec2, max_speed: f32, slowing_radius: f32) -> B2vec2 {
+    let distance = offset.length();
+    if distance <= slowing_radius {
+        with_length(offset, max_speed * distance / slowing_radius.max(f32::EPSILON))
+    } else {
+        with_length(offset, max_speed)
+    }
+}
+
+/// away from anything in the way of the current velocity, stronger the closer it is
+pub fn avoid_obstacles(velocity: B2vec2, nearby: &[PerceivedBody], look_ahead: f32, radius: f32, max_speed: f32) -> B2vec2 {
+    let direction = with_length(velocity, 1.0);
+    if direction.length() <= f32::EPSILON {
+        return B2vec2::zero();
+    }
+    let mut avoid = B2vec2::zero();
+    for body in nearby {
+        let ahead = dot(body.offset, direction);
+        if ahead <= 0.0 || ahead > look_ahead {
+            continue;
+        }
+        // from the body to the path of the character, perpendicular to the path
+        let lateral = scaled(direction, ahead) - body.offset;
+        if lateral.length() > radius {
+            continue;
+        }
+        let away = if lateral.length() > f32::EPSILON { lateral } else { B2vec2::new(-direction.y, direction.x) };
+        avoid += with_length(away, max_speed * (1.0 - ahead / look_ahead));
+    }
+    avoid
+}
+
+/// along the wall keeping the given distance from it, the wall is the offset to its closest point
+pub fn follow_wall(velocity: B2vec2, wall: B2vec2, distance: f32, max_speed: f32) -> B2vec2 {
+    let normal = with_length(B2vec2::zero() - wall, 1.0);
+    if normal.length() <= f32::EPSILON {
+        return B2vec2::zero();
+    }
+    // keep going whichever way along the wall the character is already heading
+    let mut tangent = B2vec2::new(-normal.y, normal.x);
+    if dot(tangent, velocity) < 0.0 {
+        tangent = B2vec2::zero() - tangent;
+    }
+    let correction = scaled(normal, (distance - wall.length()) / distance.max(f32::EPSILON));
+    with_length(tangent + correction, max_speed)
+}
+
+/// Meanders by nudging a point on a circle ahead of the character
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Wander {
+    /// radians around the circle
+    angle: f32
+}
+
+impl Wander {
+    /// jitter is the most the angle changes in radians per second
+    pub fn update<R: Rng>(&mut self, rng: &mut R, delta: f32, velocity: B2vec2, jitter: f32, max_speed: f32) -> B2vec2 {
+        self.angle += rng.gen_range(-1.0..=1.0) * jitter * delta;
+        let ahead = if velocity.length() <= f32::EPSILON { B2vec2::new(1.0, 0.0) } else { with_length(velocity, 1.0) };
+        let (sin, cos) = self.angle.sin_cos();
+        // circle of half the speed centered a whole speed ahead
+        let target = ahead + scaled(B2vec2::new(cos, sin), 0.5);
+        with_length(target, max_speed)
+    }
+}
+
+/// weighted sum of desired velocities, no faster than the max speed
+pub fn combine(behaviours: &[(B2vec2, f32)], max_speed: f32) -> B2vec2 {
+    let total = behaviours.iter()
+        .fold(B2vec2::zero(), |total, &(velocity, weight)| total + scaled(velocity, weight));
+    truncate(total, max_speed)
+}
+
+/// moves the velocity towards the desired velocity, changing by no more than the max acceleration
+pub fn blend(velocity: B2vec2, desired: B2vec2, max_acceleration: f32, delta: f32) -> B2vec2 {
+    velocity + truncate(desired - velocity, max_acceleration * delta)
+}
+
+fn dot(a: B2vec2, b: B2vec2) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+fn scaled(v: B2vec2, scale: f32) -> B2vec2 {
+    B2vec2::new(v.x * scale, v.y * scale)
+}
+
+fn with_length(v: B2vec2, length: f32) -> B2vec2 {
+    let current = v.length();
+    if current <= f32::EPSILON {
+        B2vec2::zero()
+    } else {
+        scaled(v, length / current)
+    }
+}
+
+fn truncate(v: B2vec2, max_length: f32) -> B2vec2 {
+    if v.length() > max_length {
+        with_length(v, max_length)
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use crate::game::event::BodyKind;
+    use super::*;
+
+    fn assert_near(actual: B2vec2, expected: B2vec2) {
+        assert!((actual - expected).length() < 1e-4, "{:?} != {:?}", actual, expected);
+    }
+
+    fn body_at(offset: B2vec2) -> PerceivedBody {
+        PerceivedBody { id: 1, kind: BodyKind::Alphanumeric, offset, velocity: B2vec2::zero(), distance: offset.length(), visible: true }
+    }
+
+    #[test]
+    fn seek_and_flee_go_full_speed() {
+        assert_near(seek(B2vec2::new(3.0, 4.0), 2.0), B2vec2::new(1.2, 1.6));
+        assert_near(flee(B2vec2::new(3.0, 4.0), 2.0), B2vec2::new(-1.2, -1.6));
+    }
+
+    #[test]
+    fn arrive_slows_down_near_the_target() {
+        assert_near(arrive(B2vec2::new(10.0, 0.0), 2.0, 1.0), B2vec2::new(2.0, 0.0));
+        assert_near(arrive(B2vec2::new(0.5, 0.0), 2.0, 1.0), B2vec2::new(1.0, 0.0));
+        assert_near(arrive(B2vec2::zero(), 2.0, 1.0), B2vec2::zero());
+    }
+
+    #[test]
+    fn avoids_only_what_is_ahead() {
+        let velocity = B2vec2::new(1.0, 0.0);
+        let behind = [body_at(B2vec2::new(-1.0, 0.1))];
+        assert_near(avoid_obstacles(velocity, &behind, 2.0, 0.5, 1.0), B2vec2::zero());
+
+        let ahead = [body_at(B2vec2::new(1.0, 0.1))];
+        let avoid = avoid_obstacles(velocity, &ahead, 2.0, 0.5, 1.0);
+        // steers to the side away from the body
+        assert!(avoid.y < 0.0);
+        assert!(avoid.x.abs() < 1e-4);
+    }
+
+    #[test]
+    fn follows_wall_in_the_current_direction() {
+        // wall below, heading left
+        let velocity = follow_wall(B2vec2::new(-1.0, 0.0), B2vec2::new(0.0, 1.0), 1.0, 1.0);
+        assert_near(velocity, B2vec2::new(-1.0, 0.0));
+
+        // too close to the wall, so pulls away from it
+        let velocity = follow_wall(B2vec2::new(1.0, 0.0), B2vec2::new(0.0, 0.5), 1.0, 1.0);
+        assert!(velocity.x > 0.0 && velocity.y < 0.0);
+    }
+
+    #[test]
+    fn wander_keeps_max_speed() {
+        let mut wander = Wander::default();
+        let velocity = wander.update(&mut thread_rng(), 0.1, B2vec2::new(0.0, 1.0), 1.0, 2.0);
+        assert!((velocity.length() - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn combine_caps_speed() {
+        let velocity = combine(&[(B2vec2::new(2.0, 0.0), 1.0), (B2vec2::new(2.0, 0.0), 0.5)], 2.0);
+        assert_near(velocity, B2vec2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn blend_limits_acceleration() {
+        let velocity = blend(B2vec2::zero(), B2vec2::new(10.0, 0.0), 5.0, 0.1);
+        assert_near(velocity, B2vec2::new(0.5, 0.0));
+        let velocity = blend(B2vec2::zero(), B2vec2::new(0.1, 0.0), 5.0, 0.1);
+        assert_near(velocity, B2vec2::new(0.1, 0.0));
+    }
+}
@@ -1,20 +1,32 @@
+use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 use crate::build_info::APP_NAME;
 use confy::ConfyError;
 use sdl2::keyboard::Keycode;
 use sdl2::mixer::MAX_VOLUME;
+use sdl2::mouse::MouseButton;
 use serde::{Deserialize, Serialize};
+use crate::assets::levels::LevelName;
+use crate::characters::{CharacterType, registry};
+use crate::characters::interaction::Interaction;
+use crate::game::action::ExplosionTarget;
+use crate::animate::nuke::NukePattern;
 
 pub const APP_CONFIG_ROOT: &str = APP_NAME;
 const CONFIG_NAME: &str = "config";
+const SESSION_NAME: &str = "session";
+const CHARACTERS_DIRECTORY: &str = "characters";
+const LEVELS_DIRECTORY: &str = "levels";
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub input: InputConfig,
     pub video: VideoConfig,
     pub audio: AudioConfig,
-    pub physics: PhysicsConfig
+    pub physics: PhysicsConfig,
+    pub characters: CharacterConfig
 }
 
 impl Config {
@@ -34,16 +46,47 @@ impl Config {
             Err(error) => Err(format!("{}", error)),
         }
     }
+
+    /// where the game is saved between sessions, alongside the config file
+    pub fn session_path() -> Result<PathBuf, String> {
+        confy::get_configuration_file_path(APP_CONFIG_ROOT, SESSION_NAME)
+            .map(|path| path.with_extension("json"))
+            .map_err(|e| e.to_string())
+    }
+
+    /// directory of character definition files, alongside the config file
+    pub fn characters_path() -> Result<PathBuf, String> {
+        let config_path = confy::get_configuration_file_path(APP_CONFIG_ROOT, CONFIG_NAME)
+            .map_err(|e| e.to_string())?;
+        Ok(config_path.with_file_name(CHARACTERS_DIRECTORY))
+    }
+
+    /// directory of level files, alongside the config file
+    pub fn levels_path() -> Result<PathBuf, String> {
+        let config_path = confy::get_configuration_file_path(APP_CONFIG_ROOT, CONFIG_NAME)
+            .map_err(|e| e.to_string())?;
+        Ok(config_path.with_file_name(LEVELS_DIRECTORY))
+    }
 }
 
+/// settings missing from an older config file keep their defaults
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InputConfig {
     pub run_toddler_sandbox: bool,
     pub baby_smash_mode: bool,
     #[serde(with = "KeycodeDef")]
     pub quit: Keycode,
+    /// switches between a window & fullscreen desktop
+    #[serde(with = "KeycodeDef")]
+    pub toggle_fullscreen: Keycode,
     pub player1: PlayerInputConfig,
     pub player2: Option<PlayerInputConfig>,
+    pub explosion_target: ExplosionTarget,
+    /// clicking this button explodes at the pointer
+    #[serde(with = "MouseButtonDef")]
+    pub explosion_button: MouseButton,
+    pub nuke_pattern: NukePattern,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -66,6 +109,15 @@ pub struct PlayerInputConfig {
     pub spawn_asset: Keycode,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CharacterConfig {
+    /// relative chance of each character being spawned at random, missing characters use their default
+    pub weights: HashMap<CharacterType, f32>,
+    /// what each character does when it meets another, missing pairs use their default
+    #[serde(default)]
+    pub interactions: HashMap<CharacterType, HashMap<CharacterType, Interaction>>,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct AudioConfig {
     pub music_volume: f64,
@@ -87,16 +139,31 @@ pub enum VideoMode {
     Window { width: u32, height: u32 },
     FullScreen { width: u32, height: u32 },
     FullScreenDesktop,
+    /// one borderless window covering every display
+    SpanDisplays,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+/// Which monitor the window opens on
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplaySelection {
+    Primary,
+    /// zero based index of the display
+    Index(i32),
+    /// name of the display as reported by the OS
+    Name(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VideoConfig {
     pub mode: VideoMode,
+    #[serde(default = "primary_display")]
+    pub display: DisplaySelection,
     pub vsync: bool,
     pub disable_screensaver: bool,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PhysicsConfig {
     pub debug_draw: bool,
     pub pixels_per_meter: f32,
@@ -110,33 +177,121 @@ pub struct PhysicsConfig {
     pub body_restitution: f32,
     pub explosion_force_magnitude: f32,
     pub explosion_distance: f32,
-    pub heavy_collision_threshold: f32
+    pub explosion_chain: Option<ExplosionChainConfig>,
+    pub word_chain: Option<WordChainConfig>,
+    pub fracture: Option<FractureConfig>,
+    pub heavy_collision_threshold: f32,
+    pub collision_events: CollisionEventsConfig,
+    pub settle: Option<SettleConfig>,
+    pub level: LevelConfig,
+    pub resume_session: bool,
+    pub perception: PerceptionConfig
+}
+
+/// How characters see the bodies around them
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerceptionConfig {
+    /// number of nearby bodies each character knows about
+    pub neighbours: usize,
+    /// size of the grid cells used to find nearby bodies
+    pub cell_size: f32,
+    /// hide bodies that are behind something else
+    pub line_of_sight: bool,
+    /// meters, bodies further away aren't noticed & characters with nothing this close idle
+    pub radius: f32,
+}
+
+/// Props placed in the world
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum LevelConfig {
+    Empty,
+    Fixed(LevelName),
+    /// cycles through every level, changing after the given time
+    Rotate { every_ms: u64 },
+}
+
+/// The world is settled when every dynamic body is asleep or slow for a while
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SettleConfig {
+    /// bodies moving slower than this are considered still
+    pub velocity_threshold: f32,
+    /// how long bodies must be still before the world is settled
+    pub settle_ms: u64,
+}
+
+/// Heavy collisions are reported as events, limited so a pile of bodies cannot flood the game
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CollisionEventsConfig {
+    /// sustained number of collision events per second
+    pub max_per_second: f32,
+    /// number of collision events that can be reported at once after a quiet period
+    pub burst: f32,
+    /// normal impulse of a collision that is played at full volume
+    pub loud_impulse: f32,
+}
+
+/// Bodies hit hard enough by an explosion become secondary explosions
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ExplosionChainConfig {
+    /// minimum explosion force on a body for it to become a secondary explosion
+    pub force_threshold: f32,
+    pub delay_ms: u64,
+    /// maximum number of secondary explosion generations
+    pub max_depth: u32,
+    /// strength of each secondary explosion relative to the one that triggered it
+    pub strength_decay: f32,
+}
+
+/// Letters break into pieces on heavy impact
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FractureConfig {
+    /// minimum contact velocity for a collision to fracture a letter
+    pub impact_velocity: f32,
+    /// minimum explosion force on a letter to fracture it
+    pub explosion_force: f32,
+    /// maximum number of pieces a letter breaks into
+    pub pieces: usize,
+    /// how long pieces remain before fading away
+    pub fragment_lifetime_ms: u64,
+}
+
+/// Letters typed in quick succession are linked together like a word on a string
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WordChainConfig {
+    /// a longer pause between keystrokes starts a new word
+    pub max_keystroke_pause_ms: u64,
+    pub joint: ChainJoint,
+    /// gap between linked letters
+    pub link_length: f32,
+    /// links snap when pulled harder than this
+    pub break_force: f32,
+    /// links attached to a body caught in an explosion snap
+    pub break_on_explosion: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ChainJoint {
+    /// a slack string between letter centers
+    Distance,
+    /// a hinge between neighbouring letters
+    Revolute
+}
+
+fn primary_display() -> DisplaySelection {
+    DisplaySelection::Primary
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            input: InputConfig {
-                run_toddler_sandbox: false,
-                baby_smash_mode: true,
-                quit: Keycode::Escape,
-                player1: PlayerInputConfig {
-                    up: Keycode::Up,
-                    down: Keycode::Down,
-                    left: Keycode::Left,
-                    right: Keycode::Right,
-                    nuke: Keycode::Backspace,
-                    explosion: Keycode::Space,
-                    spawn_character: Keycode::RShift,
-                    spawn_asset: Keycode::Return,
-                },
-                player2: None
-            },
+            input: InputConfig::default(),
             video: VideoConfig {
                 mode: VideoMode::Window {
                     width: 1280,
                     height: 720,
                 },
+                display: DisplaySelection::Primary,
                 vsync: true,
                 disable_screensaver: true,
             },
@@ -144,25 +299,114 @@ impl Default for Config {
                 music_volume: 0.5,
                 effects_volume: 1.0,
             },
-            physics: PhysicsConfig {
-                debug_draw: false,
-                pixels_per_meter: 10.0,
-                polygon_scale: 20.0,
-                velocity_iterations: 8,
-                position_iterations: 3,
-                gravity: 1.0, // positive gravity as we are rendering upside down when converting between sdl & box2d
-                push_force_magnitude: 2.0,
-                body_density: 0.001,
-                body_friction: 0.3,
-                body_restitution: 0.5,
-                explosion_force_magnitude: 200.0,
-                explosion_distance: 100.0,
-                heavy_collision_threshold: 2.0
+            physics: PhysicsConfig::default(),
+            characters: CharacterConfig {
+                weights: registry::behaviours().map(|b| (b.character_type(), b.weight())).collect(),
+                interactions: HashMap::new(),
             }
         }
     }
 }
 
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            run_toddler_sandbox: false,
+            baby_smash_mode: true,
+            quit: Keycode::Escape,
+            toggle_fullscreen: Keycode::F11,
+            player1: PlayerInputConfig {
+                up: Keycode::Up,
+                down: Keycode::Down,
+                left: Keycode::Left,
+                right: Keycode::Right,
+                nuke: Keycode::Backspace,
+                explosion: Keycode::Space,
+                spawn_character: Keycode::RShift,
+                spawn_asset: Keycode::Return,
+            },
+            player2: None,
+            explosion_target: ExplosionTarget::Random,
+            explosion_button: MouseButton::Left,
+            nuke_pattern: NukePattern::Sequential,
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            debug_draw: false,
+            pixels_per_meter: 10.0,
+            polygon_scale: 20.0,
+            velocity_iterations: 8,
+            position_iterations: 3,
+            gravity: 1.0, // positive gravity as we are rendering upside down when converting between sdl & box2d
+            push_force_magnitude: 2.0,
+            body_density: 0.001,
+            body_friction: 0.3,
+            body_restitution: 0.5,
+            explosion_force_magnitude: 200.0,
+            explosion_distance: 100.0,
+            explosion_chain: Some(ExplosionChainConfig {
+                force_threshold: 60.0,
+                delay_ms: 250,
+                max_depth: 2,
+                strength_decay: 0.75,
+            }),
+            word_chain: Some(WordChainConfig {
+                max_keystroke_pause_ms: 500,
+                joint: ChainJoint::Distance,
+                link_length: 2.0,
+                break_force: 20.0,
+                break_on_explosion: true,
+            }),
+            fracture: Some(FractureConfig {
+                impact_velocity: 10.0,
+                explosion_force: 190.0,
+                pieces: 4,
+                fragment_lifetime_ms: 3000,
+            }),
+            heavy_collision_threshold: 2.0,
+            collision_events: CollisionEventsConfig {
+                max_per_second: 10.0,
+                burst: 5.0,
+                loud_impulse: 1.0,
+            },
+            settle: Some(SettleConfig {
+                velocity_threshold: 0.1,
+                settle_ms: 3000,
+            }),
+            level: LevelConfig::Rotate { every_ms: 300000 },
+            resume_session: true,
+            perception: PerceptionConfig::default()
+        }
+    }
+}
+
+impl Default for PerceptionConfig {
+    fn default() -> Self {
+        Self {
+            neighbours: 5,
+            cell_size: 20.0,
+            line_of_sight: true,
+            radius: 30.0,
+        }
+    }
+}
+
+/// redefined here for serde sigh
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "MouseButton")]
+enum MouseButtonDef {
+    Unknown,
+    Left,
+    Middle,
+    Right,
+    X1,
+    X2,
+}
+
 /// redefined here for serde sigh
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "Keycode")]
@@ -403,3 +647,23 @@ enum KeycodeDef {
     Eject = sdl2::sys::SDL_KeyCode::SDLK_EJECT as isize,
     Sleep = sdl2::sys::SDL_KeyCode::SDLK_SLEEP as isize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_files_from_before_a_setting_was_added_keep_their_settings() {
+        let mut json = serde_json::to_value(Config::default()).unwrap();
+        json["input"]["run_toddler_sandbox"] = true.into();
+        for (section, setting) in [("input", "explosion_button"), ("input", "toggle_fullscreen"), ("input", "nuke_pattern"), ("physics", "explosion_chain"), ("physics", "level")] {
+            json[section].as_object_mut().unwrap().remove(setting);
+        }
+        json["physics"]["perception"].as_object_mut().unwrap().remove("radius");
+        let config: Config = serde_json::from_value(json).unwrap();
+        assert!(config.input.run_toddler_sandbox);
+        assert_eq!(config.input.explosion_button, MouseButton::Left);
+        assert!(config.physics.explosion_chain.is_some());
+        assert_eq!(config.physics.perception.radius, PerceptionConfig::default().radius);
+    }
+}
@@ -1,10 +1,27 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, Copy)]
 pub enum Direction {
     Up, Down, Left, Right
 }
 
+/// Where an explosion should be centered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExplosionTarget {
+    /// anywhere in the world
+    Random,
+    /// a point in screen coordinates e.g. the pointer
+    Point { x: i32, y: i32 },
+    /// the most recently spawned body
+    LastSpawn,
+    /// the center of the most crowded area of the world
+    Densest,
+    /// a random character
+    Character
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum PhysicsAction {
     Push(Direction),
-    Explode
-}
\ No newline at end of file
+    Explode(ExplosionTarget)
+}
@@ -1,29 +1,46 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
+use sdl2::rect::{Point, Rect};
 use sdl2::render::WindowCanvas;
 use crate::characters::CharacterType;
+use crate::characters::interaction::InteractionMatrix;
 use crate::assets::geometry::SpriteAsset;
 use crate::config::PhysicsConfig;
-use crate::game::action::{Direction, PhysicsAction};
+use crate::game::action::{Direction, ExplosionTarget, PhysicsAction};
 use crate::game::event::GameEvent;
 use crate::game::Game;
 use crate::game::physics::{Body, Physics};
+use crate::game::query::{BodyFilter, RayHit};
 use crate::game::scale::PhysicsScale;
+use crate::game::snapshot::WorldSnapshot;
 
 
 pub struct DefaultGame {
     events: Vec<GameEvent>,
-    physics: Physics
+    physics: Physics,
+    canvas: Option<Rc<RefCell<WindowCanvas>>>
 }
 
 impl DefaultGame {
-    pub fn new<C: Into<Option<Rc<RefCell<WindowCanvas>>>>>(scale: PhysicsScale, physics_config: PhysicsConfig, canvas: C) -> Self {
-        let mut physics = Physics::new(scale, physics_config);
-        if let Some(canvas) = canvas.into() {
-            physics.set_sdl_debug_draw(canvas);
+    pub fn new<C: Into<Option<Rc<RefCell<WindowCanvas>>>>>(
+        scale: PhysicsScale,
+        physics_config: PhysicsConfig,
+        interactions: InteractionMatrix,
+        canvas: C
+    ) -> Self {
+        let mut physics = Physics::new(scale, physics_config).with_interactions(interactions);
+        let canvas = canvas.into();
+        if let Some(canvas) = &canvas {
+            physics.set_sdl_debug_draw(canvas.clone());
         }
-        Self { events: vec![], physics }
+        Self { events: vec![], physics, canvas }
+    }
+
+    /// clears the buffer & fills it with every body, reusing its allocation
+    pub fn bodies_into(&self, bodies: &mut Vec<Body>) {
+        self.physics.bodies_into(bodies);
     }
 }
 
@@ -34,8 +51,8 @@ impl Game for DefaultGame {
         }
     }
 
-    fn spawn_asset(&mut self, sprite: SpriteAsset) {
-        for event in self.physics.spawn_asset(sprite).into_iter() {
+    fn spawn_asset(&mut self, sprite: SpriteAsset, typed: bool) {
+        for event in self.physics.spawn_asset(sprite, typed).into_iter() {
             self.events.push(event);
         }
     }
@@ -52,8 +69,8 @@ impl Game for DefaultGame {
         }
     }
 
-    fn explosion(&mut self) {
-        for event in self.physics.action(PhysicsAction::Explode) {
+    fn explosion(&mut self, target: ExplosionTarget) {
+        for event in self.physics.action(PhysicsAction::Explode(target)) {
             self.events.push(event);
         }
     }
@@ -69,8 +86,40 @@ impl Game for DefaultGame {
         buffer
     }
 
-    fn bodies(&self) -> Vec<Body> {
-        self.physics.bodies()
+    fn bodies(&self) -> Arc<Vec<Body>> {
+        Arc::new(self.physics.bodies())
+    }
+
+    fn body_at(&self, point: Point) -> Option<Body> {
+        self.physics.body_at(point)
+    }
+
+    fn bodies_in_rect(&self, rect: Rect) -> Vec<Body> {
+        self.physics.bodies_in_rect(rect)
+    }
+
+    fn ray_cast(&self, from: Point, to: Point) -> Option<RayHit> {
+        self.physics.ray_cast(from, to)
+    }
+
+    fn nearest(&self, point: Point, filter: BodyFilter) -> Option<Body> {
+        self.physics.nearest(point, filter)
+    }
+
+    fn snapshot(&self) -> WorldSnapshot {
+        self.physics.snapshot()
+    }
+
+    fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.physics.restore(snapshot);
+    }
+
+    fn resize(&mut self, scale: PhysicsScale) {
+        self.physics.resize(scale);
+        if let Some(canvas) = &self.canvas {
+            // the debug draw has its own copy of the scale
+            self.physics.set_sdl_debug_draw(canvas.clone());
+        }
     }
 
     fn debug_draw(&self) {
@@ -1,11 +1,68 @@
+use sdl2::rect::Point;
+use crate::assets::geometry::Material;
 use crate::characters::CharacterType;
+use crate::characters::lifetime::CharacterState;
 use crate::game::physics::Body;
 
 #[derive(Clone, Debug)]
 pub enum GameEvent {
     Spawned(Body),
     Destroy(Body),
-    Explosion { x: i32, y: i32 },
+    Explosion { x: i32, y: i32, radius: u32, strength: f32 },
     CharacterAttack(CharacterType),
-    HeavyCollision
+    /// a character bit into a letter of the color at the screen coordinates where they touched
+    CharacterMunch { point: Point, color: (u8, u8, u8) },
+    /// one character ate another
+    CharacterEats { predator: CharacterType, prey: CharacterType },
+    /// a character ate a power pellet e.g. pac-man
+    CharacterPoweredUp(CharacterType),
+    /// a character changed from one state to another, e.g. fell asleep
+    CharacterStateChanged { id: u128, character: CharacterType, from: CharacterState, to: CharacterState },
+    Fractured { parent: Body, pieces: Vec<Body> },
+    Collision(Collision),
+    /// every dynamic body has been still for the configured time
+    WorldSettled,
+    /// something moved after the world settled
+    WorldWoke
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum BodyKind {
+    Unknown,
+    Alphanumeric,
+    Character(CharacterType),
+    Fragment,
+    /// part of a character made from more than one body
+    Segment { owner: u128 }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct CollisionBody {
+    pub id: u128,
+    pub kind: BodyKind,
+    pub material: Material
+}
+
+impl CollisionBody {
+    pub fn is_character(&self) -> bool {
+        matches!(self.kind, BodyKind::Character(_))
+    }
+}
+
+/// A heavy collision between a body and another body or the ground
+#[derive(Debug, Clone, Copy)]
+pub struct Collision {
+    /// screen coordinates of the contact point
+    pub point: Point,
+    pub body: CollisionBody,
+    /// none when the body hit the ground
+    pub other: Option<CollisionBody>,
+    /// largest normal impulse of the contact
+    pub impulse: f32
+}
+
+impl Collision {
+    pub fn involves_character(&self) -> bool {
+        self.body.is_character() || self.other.map(|o| o.is_character()).unwrap_or(false)
+    }
 }
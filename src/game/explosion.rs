@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use box2d_rs::b2_math::B2vec2;
+use crate::config::ExplosionChainConfig;
+
+/// A body that will become a secondary explosion
+#[derive(Debug, Clone, Copy)]
+pub struct PendingExplosion {
+    pub body_id: u128,
+    delay: Duration,
+    pub strength: f32,
+    pub depth: u32
+}
+
+/// Bodies caught in explosions that go off themselves after a delay
+#[derive(Debug, Default)]
+pub struct ChainReaction {
+    pending: Vec<PendingExplosion>
+}
+
+impl ChainReaction {
+    /// queues the body as a secondary explosion of an explosion of the given strength & depth,
+    /// if it was hit hard enough & the chain isn't too deep yet
+    pub fn catch(&mut self, chain: ExplosionChainConfig, body_id: u128, force: f32, strength: f32, depth: u32) {
+        if depth >= chain.max_depth || force <= chain.force_threshold {
+            return;
+        }
+        if self.pending.iter().any(|pending| pending.body_id == body_id) {
+            return;
+        }
+        self.pending.push(PendingExplosion {
+            body_id,
+            delay: Duration::from_millis(chain.delay_ms),
+            strength: strength * chain.strength_decay,
+            depth: depth + 1
+        });
+    }
+
+    /// burns down the fuses, returns the explosions that are due
+    pub fn update(&mut self, delta: Duration) -> Vec<PendingExplosion> {
+        let mut due = vec![];
+        self.pending.retain_mut(|pending| {
+            match pending.delay.checked_sub(delta) {
+                Some(delay) if !delay.is_zero() => {
+                    pending.delay = delay;
+                    true
+                }
+                _ => {
+                    due.push(*pending);
+                    false
+                }
+            }
+        });
+        due
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// force of an explosion on a body at the distance from its center,
+/// inversely proportional to the distance & not positive outside of the explosion
+pub fn force(force_magnitude: f32, distance_magnitude: f32, distance: f32) -> f32 {
+    force_magnitude - (distance / distance_magnitude) * force_magnitude
+}
+
+/// the average of the positions in the most crowded cell of a grid with the cell size
+pub fn densest_location<I: Iterator<Item=B2vec2>>(positions: I, cell_size: f32) -> Option<B2vec2> {
+    let mut cells: HashMap<(i32, i32), Vec<B2vec2>> = HashMap::new();
+    for position in positions {
+        let cell = ((position.x / cell_size).floor() as i32, (position.y / cell_size).floor() as i32);
+        cells.entry(cell).or_default().push(position);
+    }
+
+    cells.into_values()
+        .max_by_key(|positions| positions.len())
+        .map(|positions| {
+            let mut sum = B2vec2::zero();
+            for position in positions.iter() {
+                sum += *position;
+            }
+            sum *= 1.0 / positions.len() as f32;
+            sum
+        })
+}
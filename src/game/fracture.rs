@@ -0,0 +1,136 @@
+use std::collections::{HashMap, VecDeque};
+use box2d_rs::b2_math::B2vec2;
+use rand::Rng;
+use rand::seq::index::sample;
+
+/// vertices are considered shared when they are equal to this precision
+const VERTEX_PRECISION: f32 = 1000.0;
+
+/// Splits triangles into pieces of connected triangles, where triangles are connected by shared vertices.
+/// Returns the indices of the triangles in each piece, there will be at most `pieces` pieces
+/// unless the triangles are already disconnected islands e.g. the dot of an 'i'
+pub fn fracture<R: Rng>(triangles: &[[B2vec2; 3]], pieces: usize, rng: &mut R) -> Vec<Vec<usize>> {
+    let neighbours = neighbours(triangles);
+    let mut piece_of: Vec<Option<usize>> = vec![None; triangles.len()];
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut queues: Vec<VecDeque<usize>> = vec![];
+
+    fn add_group(seed: usize, piece_of: &mut [Option<usize>], groups: &mut Vec<Vec<usize>>, queues: &mut Vec<VecDeque<usize>>) {
+        piece_of[seed] = Some(groups.len());
+        groups.push(vec![seed]);
+        queues.push(VecDeque::from([seed]));
+    }
+
+    for seed in sample(rng, triangles.len(), pieces.min(triangles.len())).into_iter() {
+        add_group(seed, &mut piece_of, &mut groups, &mut queues);
+    }
+
+    loop {
+        // grow all pieces in lockstep so they end up a similar size
+        while queues.iter().any(|q| !q.is_empty()) {
+            for (piece, queue) in queues.iter_mut().enumerate() {
+                if let Some(triangle) = queue.pop_front() {
+                    for &neighbour in neighbours[triangle].iter() {
+                        if piece_of[neighbour].is_none() {
+                            piece_of[neighbour] = Some(piece);
+                            groups[piece].push(neighbour);
+                            queue.push_back(neighbour);
+                        }
+                    }
+                }
+            }
+        }
+
+        // islands not reachable from any seed become pieces of their own
+        match piece_of.iter().position(|p| p.is_none()) {
+            Some(seed) => add_group(seed, &mut piece_of, &mut groups, &mut queues),
+            None => break
+        }
+    }
+
+    groups
+}
+
+fn neighbours(triangles: &[[B2vec2; 3]]) -> Vec<Vec<usize>> {
+    let key = |v: B2vec2| ((v.x * VERTEX_PRECISION).round() as i64, (v.y * VERTEX_PRECISION).round() as i64);
+
+    let mut by_vertex: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (index, triangle) in triangles.iter().enumerate() {
+        for vertex in triangle.iter() {
+            by_vertex.entry(key(*vertex)).or_default().push(index);
+        }
+    }
+
+    triangles.iter().enumerate()
+        .map(|(index, triangle)| {
+            let mut neighbours = triangle.iter()
+                .flat_map(|v| by_vertex[&key(*v)].iter().copied())
+                .filter(|&other| other != index)
+                .collect::<Vec<usize>>();
+            neighbours.sort();
+            neighbours.dedup();
+            neighbours
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use super::*;
+
+    /// a horizontal strip of triangles, each sharing an edge with the next
+    fn strip(count: usize) -> Vec<[B2vec2; 3]> {
+        (0..count).map(|i| {
+            let x = (i / 2) as f32;
+            if i % 2 == 0 {
+                [B2vec2::new(x, 0.0), B2vec2::new(x + 1.0, 0.0), B2vec2::new(x, 1.0)]
+            } else {
+                [B2vec2::new(x + 1.0, 0.0), B2vec2::new(x + 1.0, 1.0), B2vec2::new(x, 1.0)]
+            }
+        }).collect()
+    }
+
+    fn assert_connected(triangles: &[[B2vec2; 3]], group: &[usize]) {
+        let neighbours = neighbours(triangles);
+        let mut visited = vec![group[0]];
+        let mut queue = VecDeque::from([group[0]]);
+        while let Some(triangle) = queue.pop_front() {
+            for neighbour in neighbours[triangle].iter() {
+                if group.contains(neighbour) && !visited.contains(neighbour) {
+                    visited.push(*neighbour);
+                    queue.push_back(*neighbour);
+                }
+            }
+        }
+        assert_eq!(visited.len(), group.len());
+    }
+
+    #[test]
+    fn every_triangle_is_in_one_piece() {
+        let triangles = strip(12);
+        let groups = fracture(&triangles, 4, &mut StdRng::seed_from_u64(1));
+        assert_eq!(groups.len(), 4);
+
+        let mut all = groups.concat();
+        all.sort();
+        assert_eq!(all, (0..12).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn pieces_are_connected() {
+        let triangles = strip(20);
+        for group in fracture(&triangles, 3, &mut StdRng::seed_from_u64(2)) {
+            assert_connected(&triangles, &group);
+        }
+    }
+
+    #[test]
+    fn islands_are_separate_pieces() {
+        let mut triangles = strip(4);
+        triangles.push([B2vec2::new(10.0, 10.0), B2vec2::new(11.0, 10.0), B2vec2::new(10.0, 11.0)]);
+        let groups = fracture(&triangles, 1, &mut StdRng::seed_from_u64(3));
+        assert_eq!(groups.len(), 2);
+    }
+}
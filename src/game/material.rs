@@ -0,0 +1,71 @@
+use crate::assets::geometry::Material;
+use crate::config::PhysicsConfig;
+
+/// The bank of sounds played on a heavy collision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollisionSound {
+    /// any collision sound
+    Mixed,
+    Hard,
+    Soft
+}
+
+/// Physical properties of a material, relative to the configured body properties
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialProperties {
+    density: f32,
+    friction: f32,
+    restitution: f32,
+    gravity_scale: f32,
+    heavy_collision_threshold: f32
+}
+
+impl MaterialProperties {
+    pub fn new(material: Material, config: &PhysicsConfig) -> Self {
+        // (density, friction, restitution, gravity scale, heavy collision threshold) multipliers
+        let (density, friction, restitution, gravity_scale, threshold) = match material {
+            Material::Default => (1.0, 1.0, 1.0, 1.0, 1.0),
+            Material::Rubber => (1.0, 2.0, 1.8, 1.0, 2.0),
+            Material::Wood => (0.6, 1.5, 0.6, 1.0, 1.0),
+            Material::Metal => (3.0, 0.5, 0.2, 1.0, 0.5),
+            Material::Jelly => (0.8, 3.0, 0.4, 1.0, 3.0),
+            // floats away
+            Material::Balloon => (0.1, 1.0, 1.2, -0.3, 2.0),
+        };
+        Self {
+            density: config.body_density * density,
+            friction: config.body_friction * friction,
+            restitution: (config.body_restitution * restitution).min(1.0),
+            gravity_scale,
+            heavy_collision_threshold: config.heavy_collision_threshold * threshold
+        }
+    }
+
+    pub fn density(&self) -> f32 {
+        self.density
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    pub fn gravity_scale(&self) -> f32 {
+        self.gravity_scale
+    }
+
+    pub fn heavy_collision_threshold(&self) -> f32 {
+        self.heavy_collision_threshold
+    }
+}
+
+pub fn collision_sound(material: Material) -> CollisionSound {
+    match material {
+        Material::Default => CollisionSound::Mixed,
+        Material::Wood | Material::Metal => CollisionSound::Hard,
+        Material::Rubber | Material::Jelly | Material::Balloon => CollisionSound::Soft
+    }
+}
@@ -1,15 +1,20 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
+use sdl2::rect::{Point, Rect};
 use sdl2::render::WindowCanvas;
 use crate::characters::CharacterType;
+use crate::characters::interaction::InteractionMatrix;
 use crate::assets::geometry::SpriteAsset;
 use crate::config::PhysicsConfig;
-use crate::game::action::Direction;
+use crate::game::action::{Direction, ExplosionTarget};
 use crate::game::default::DefaultGame;
 use crate::game::event::GameEvent;
 use crate::game::physics::Body;
+use crate::game::query::{BodyFilter, RayHit};
 use crate::game::scale::PhysicsScale;
+use crate::game::snapshot::WorldSnapshot;
 use crate::game::sync::AsyncGame;
 
 pub mod event;
@@ -18,28 +23,43 @@ mod physics_debug;
 pub mod polygon;
 pub mod scale;
 pub mod action;
+pub mod material;
+pub mod query;
+pub mod snapshot;
+mod explosion;
+mod fracture;
+mod perception;
 mod sync;
 mod default;
 
 pub trait Game {
     fn push(&mut self, direction: Direction);
-    fn spawn_asset(&mut self, sprite: SpriteAsset);
+    fn spawn_asset(&mut self, sprite: SpriteAsset, typed: bool);
     fn spawn_character(&mut self, character: CharacterType);
     fn destroy(&mut self, id: u128);
-    fn explosion(&mut self);
+    fn explosion(&mut self, target: ExplosionTarget);
     fn update(&mut self, delta: Duration) -> Vec<GameEvent>;
-    fn bodies(&self) -> Vec<Body>;
+    fn bodies(&self) -> Arc<Vec<Body>>;
+    fn body_at(&self, point: Point) -> Option<Body>;
+    fn bodies_in_rect(&self, rect: Rect) -> Vec<Body>;
+    fn ray_cast(&self, from: Point, to: Point) -> Option<RayHit>;
+    fn nearest(&self, point: Point, filter: BodyFilter) -> Option<Body>;
+    fn snapshot(&self) -> WorldSnapshot;
+    fn restore(&mut self, snapshot: WorldSnapshot);
+    /// the window changed size
+    fn resize(&mut self, scale: PhysicsScale);
     fn debug_draw(&self);
 }
 
 pub fn game<C: Into<Option<Rc<RefCell<WindowCanvas>>>>>(
     scale: PhysicsScale,
     physics_config: PhysicsConfig,
+    interactions: InteractionMatrix,
     canvas: C
 ) -> Box<dyn Game> {
     if physics_config.debug_draw {
-        Box::new(DefaultGame::new(scale, physics_config, canvas))
+        Box::new(DefaultGame::new(scale, physics_config, interactions, canvas))
     } else {
-        Box::new(AsyncGame::new(scale, physics_config))
+        Box::new(AsyncGame::new(scale, physics_config, interactions))
     }
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use box2d_rs::b2_math::B2vec2;
+use crate::game::event::BodyKind;
+
+/// A body that characters can perceive
+#[derive(Debug, Clone, Copy)]
+pub struct Perceivable {
+    pub id: u128,
+    pub kind: BodyKind,
+    pub position: B2vec2,
+    pub velocity: B2vec2
+}
+
+/// Uniform grid over body positions, so nearest neighbour queries only visit cells around the query
+pub struct PerceptionGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<Perceivable>>,
+    lower: (i32, i32),
+    upper: (i32, i32)
+}
+
+impl PerceptionGrid {
+    pub fn new<I: IntoIterator<Item = Perceivable>>(cell_size: f32, bodies: I) -> Self {
+        let mut grid = Self { cell_size, cells: HashMap::new(), lower: (i32::MAX, i32::MAX), upper: (i32::MIN, i32::MIN) };
+        for body in bodies {
+            let (x, y) = grid.cell(body.position);
+            grid.lower = (grid.lower.0.min(x), grid.lower.1.min(y));
+            grid.upper = (grid.upper.0.max(x), grid.upper.1.max(y));
+            grid.cells.entry((x, y)).or_default().push(body);
+        }
+        grid
+    }
+
+    fn cell(&self, position: B2vec2) -> (i32, i32) {
+        ((position.x / self.cell_size).floor() as i32, (position.y / self.cell_size).floor() as i32)
+    }
+
+    /// up to k bodies closest to the position that match the filter, nearest first.
+    /// The body with id `exclude` is never returned, so a character does not perceive itself
+    pub fn k_nearest<F: Fn(&Perceivable) -> bool>(&self, position: B2vec2, k: usize, exclude: u128, filter: F) -> Vec<(Perceivable, f32)> {
+        let mut found: Vec<(Perceivable, f32)> = vec![];
+        if k == 0 || self.cells.is_empty() {
+            return found;
+        }
+
+        let (cx, cy) = self.cell(position);
+        // rings beyond this cover no occupied cells
+        let max_ring = [cx - self.lower.0, self.upper.0 - cx, cy - self.lower.1, self.upper.1 - cy]
+            .into_iter()
+            .max()
+            .unwrap()
+            .max(0);
+
+        for ring in 0..=max_ring {
+            for x in (cx - ring)..=(cx + ring) {
+                for y in (cy - ring)..=(cy + ring) {
+                    // only the cells on the edge of the ring are new
+                    if (x - cx).abs() != ring && (y - cy).abs() != ring {
+                        continue;
+                    }
+                    for body in self.cells.get(&(x, y)).into_iter().flatten() {
+                        if body.id != exclude && filter(body) {
+                            found.push((*body, (body.position - position).length()));
+                        }
+                    }
+                }
+            }
+
+            found.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+            found.truncate(k);
+            // every body within this distance has been visited, so the result cannot change
+            if found.len() == k && found[k - 1].1 <= ring as f32 * self.cell_size {
+                break;
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(id: u128, x: f32, y: f32) -> Perceivable {
+        Perceivable { id, kind: BodyKind::Alphanumeric, position: B2vec2::new(x, y), velocity: B2vec2::zero() }
+    }
+
+    fn ids(found: &[(Perceivable, f32)]) -> Vec<u128> {
+        found.iter().map(|(b, _)| b.id).collect()
+    }
+
+    #[test]
+    fn excludes_self() {
+        let grid = PerceptionGrid::new(10.0, [body(1, 5.0, 5.0), body(2, 6.0, 5.0)]);
+        assert_eq!(ids(&grid.k_nearest(B2vec2::new(5.0, 5.0), 5, 1, |_| true)), vec![2]);
+    }
+
+    #[test]
+    fn excludes_self_when_alone() {
+        let grid = PerceptionGrid::new(10.0, [body(1, 5.0, 5.0)]);
+        assert!(grid.k_nearest(B2vec2::new(5.0, 5.0), 5, 1, |_| true).is_empty());
+    }
+
+    #[test]
+    fn does_not_exclude_other_bodies_at_the_same_position() {
+        let grid = PerceptionGrid::new(10.0, [body(1, 5.0, 5.0), body(2, 5.0, 5.0)]);
+        assert_eq!(ids(&grid.k_nearest(B2vec2::new(5.0, 5.0), 5, 1, |_| true)), vec![2]);
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let bodies = (0..200u128)
+            // offset by the id so no two bodies are the same distance away
+            .map(|i| body(i, ((i * 37) % 101) as f32 + i as f32 * 0.001, ((i * 53) % 97) as f32))
+            .collect::<Vec<Perceivable>>();
+        let grid = PerceptionGrid::new(7.0, bodies.clone());
+        let position = B2vec2::new(40.0, 60.0);
+
+        let mut expected = bodies.iter()
+            .filter(|b| b.id != 3)
+            .map(|b| (b.id, (b.position - position).length()))
+            .collect::<Vec<(u128, f32)>>();
+        expected.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        let expected = expected.into_iter().take(8).map(|(id, _)| id).collect::<Vec<u128>>();
+
+        assert_eq!(ids(&grid.k_nearest(position, 8, 3, |_| true)), expected);
+    }
+
+    #[test]
+    fn applies_filter() {
+        let mut character = body(2, 1.0, 1.0);
+        character.kind = BodyKind::Fragment;
+        let grid = PerceptionGrid::new(10.0, [character, body(3, 50.0, 50.0)]);
+        let found = grid.k_nearest(B2vec2::new(0.0, 0.0), 1, 0, |b| b.kind == BodyKind::Alphanumeric);
+        assert_eq!(ids(&found), vec![3]);
+    }
+}
@@ -1,46 +1,99 @@
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::f64::consts::PI;
 use std::ops::Sub;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 use box2d_rs::b2_body::{B2body, B2bodyDef, B2bodyType, BodyPtr};
-use box2d_rs::b2_collision::{B2AABB, B2worldManifold};
+use box2d_rs::b2_collision::{B2AABB, B2manifold, B2worldManifold};
 use box2d_rs::b2_contact::B2contactDynTrait;
 use box2d_rs::b2_fixture::{B2fixture, B2fixtureDef, FixturePtr};
+use box2d_rs::b2_joint::{B2JointDefEnum, B2jointPtr};
 use box2d_rs::b2_math::{b2_mul_transform_by_vec2, B2Transform, B2vec2};
 use box2d_rs::b2_world::{B2world, B2worldPtr};
 use box2d_rs::b2_world_callbacks::{B2contactImpulse, B2contactListener};
 use box2d_rs::b2rs_common::UserDataType;
+use box2d_rs::joints::b2_distance_joint::B2distanceJointDef;
+use box2d_rs::joints::b2_revolute_joint::B2revoluteJointDef;
+use box2d_rs::joints::b2_weld_joint::B2weldJointDef;
 use box2d_rs::shapes::b2_edge_shape::B2edgeShape;
 use box2d_rs::shapes::b2_polygon_shape::B2polygonShape;
 use rand::rngs::ThreadRng;
 use rand::{Rng, thread_rng};
+use rand::seq::SliceRandom;
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::WindowCanvas;
-use crate::characters::{Character, CharacterFactory, CharacterType, CharacterWorldState};
-use crate::assets::geometry::SpriteAsset;
+use serde::{Deserialize, Serialize};
+use crate::characters::{Character, CharacterFactory, CharacterType, CharacterWorldState, PerceivedBody};
+use crate::characters::interaction::{Interaction, InteractionMatrix};
+use crate::assets::geometry::{Material, SpriteAsset, SpriteId};
 use crate::characters::lifetime::CharacterState;
-use crate::config::PhysicsConfig;
-use crate::game::action::{Direction, PhysicsAction};
-use crate::game::event::{GameEvent};
+use crate::assets::levels::{Level, LevelName, Prop, PropMotion};
+use crate::config::{ChainJoint, LevelConfig, PhysicsConfig};
+use crate::game::action::{Direction, ExplosionTarget, PhysicsAction};
+use crate::game::event::{BodyKind, Collision, CollisionBody, GameEvent};
+use crate::game::explosion::{self, ChainReaction};
+use crate::game::fracture;
+use crate::game::material::MaterialProperties;
+use crate::game::perception::{Perceivable, PerceptionGrid};
 use crate::game::physics_debug::SdlPhysicsDraw;
 use crate::game::polygon::Triangle;
+use crate::game::query::{BodyFilter, RayHit};
+use crate::game::snapshot::{BodyMotion, BodySnapshot, SNAPSHOT_VERSION, WorldSnapshot};
 use crate::game::scale::PhysicsScale;
 
-#[derive(Debug, Clone)]
+/// segments just behind a character's head that it can't help brushing against as it turns
+const NECK_SEGMENTS: usize = 2;
+/// random spots tried before bodies in the way are destroyed to make room
+const CLEAR_SPAWN_ATTEMPTS: usize = 10;
+/// random spots tried before spawning inside a prop, only for levels with almost no room
+const SPAWN_ATTEMPTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct AlphanumericBody {
-    pub name: String,
+    /// depends on the order sprites are loaded so snapshots save the sprite's name instead
+    #[serde(skip)]
+    pub sprite: SpriteId,
     pub alphanumeric: char,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub enum BodyType {
     #[default]
     Unknown,
     Alphanumeric(AlphanumericBody),
-    Character(Character)
+    Character(Character),
+    Fragment(FragmentBody),
+    /// scenery from the level
+    Prop,
+    Segment(SegmentBody)
+}
+
+/// A body following a character that is made from more than one body e.g. the tail of a snake
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SegmentBody {
+    pub owner: u128,
+    pub character_type: CharacterType,
+    /// position in the chain, the first segment follows the owner
+    pub index: usize,
+    pub color: (u8, u8, u8)
+}
+
+/// A piece of a fractured letter, fades away over its lifetime
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FragmentBody {
+    pub parent: u128,
+    pub age: Duration,
+    pub lifetime: Duration
+}
+
+impl FragmentBody {
+    pub fn alpha(&self) -> u8 {
+        let remaining = 1.0 - self.age.as_secs_f64() / self.lifetime.as_secs_f64();
+        (remaining.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,17 +101,55 @@ pub struct Body {
     pub id: u128,
     pub aabb: Rect,
     pub angle: f64,
-    pub polygons: Vec<Triangle>,
+    /// center of the body in pixels
+    pub center: (f32, f32),
+    pub shape: BodyShape,
+    pub joints: Vec<BodyJoint>,
     pub body_type: BodyType
 }
 
+impl Body {
+    /// the triangles of the body on screen, computed on demand as most bodies are drawn from sprites
+    pub fn polygons(&self) -> Vec<Triangle> {
+        let (sin, cos) = (self.angle as f32).to_radians().sin_cos();
+        let (cx, cy) = self.center;
+        self.shape.iter()
+            .map(|triangle| {
+                let points = triangle.points.map(|(x, y)| Point::new(
+                    (cx + x * cos - y * sin).round() as i32,
+                    (cy + x * sin + y * cos).round() as i32
+                ));
+                Triangle::new(points, triangle.color)
+            })
+            .collect()
+    }
+}
+
+/// Triangles of a body in pixels relative to its center, shared by every copy of the body
+pub type BodyShape = Arc<Vec<LocalTriangle>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LocalTriangle {
+    points: [(f32, f32); 3],
+    color: Color
+}
+
+/// A joint from this body to another, in screen coordinates
+#[derive(Debug, Clone, Copy)]
+pub struct BodyJoint {
+    pub from: Point,
+    pub to: Point
+}
+
 
 #[derive(Default, Debug, Clone)]
 struct BodyData {
     id: u128,
     width: f32,
     height: f32,
-    body_type: BodyType
+    material: Material,
+    body_type: BodyType,
+    shape: BodyShape
 }
 
 #[derive(Debug, Clone)]
@@ -73,44 +164,106 @@ impl Default for FixtureData {
     }
 }
 
+#[derive(Default, Debug, Clone)]
+struct JointData {
+    id: u128
+}
+
 #[derive(Default, Debug, Clone)]
 struct UserDataTypes;
 impl UserDataType for UserDataTypes {
     type Fixture = FixtureData;
     type Body = BodyData;
-    type Joint = ();
+    type Joint = JointData;
 }
 
 pub struct Physics {
     rng: ThreadRng,
     world: B2worldPtr<UserDataTypes>,
+    ground: BodyPtr<UserDataTypes>,
     scale: PhysicsScale,
     config: PhysicsConfig,
     character_factory: CharacterFactory,
+    interactions: InteractionMatrix,
     contact_listener: Rc<RefCell<ContactListener>>,
-    time_since_last_explosion: Duration
+    time_since_last_explosion: Duration,
+    last_spawned: Option<u128>,
+    chain_reaction: ChainReaction,
+    pending_fractures: HashSet<u128>,
+    /// characters caught in an explosion since the last update
+    pending_stuns: HashSet<u128>,
+    chain_links: Vec<ChainLink>,
+    chain_tail: Option<u128>,
+    carried: Vec<CarriedBody>,
+    time_since_last_keystroke: Duration,
+    time_still: Duration,
+    settled: bool,
+    level: Option<LevelName>,
+    /// props of the current level, kept so resizing doesn't load it again
+    layout: Level,
+    time_in_level: Duration
 }
 
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
-enum ContactMagnitude { Light, Heavy }
+/// A joint between neighbouring letters of a word chain
+struct ChainLink {
+    id: u128,
+    body_a: u128,
+    body_b: u128,
+    joint: B2jointPtr<UserDataTypes>
+}
+
+/// A body held by a character until it lets go
+struct CarriedBody {
+    character: u128,
+    body: u128,
+    joint: B2jointPtr<UserDataTypes>
+}
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 struct ContactedBody {
     body_id: u128,
-    character: Option<CharacterType>
+    material: Material,
+    kind: BodyKind
+}
+
+impl ContactedBody {
+    fn character(&self) -> Option<CharacterType> {
+        match self.kind {
+            BodyKind::Character(character_type) => Some(character_type),
+            _ => None
+        }
+    }
+}
+
+impl From<&BodyType> for BodyKind {
+    fn from(value: &BodyType) -> Self {
+        match value {
+            BodyType::Unknown | BodyType::Prop => BodyKind::Unknown,
+            BodyType::Alphanumeric(_) => BodyKind::Alphanumeric,
+            BodyType::Character(character) => BodyKind::Character(character.character_type()),
+            BodyType::Fragment(_) => BodyKind::Fragment,
+            BodyType::Segment(segment) => BodyKind::Segment { owner: segment.owner },
+        }
+    }
+}
+
+impl From<ContactedBody> for CollisionBody {
+    fn from(value: ContactedBody) -> Self {
+        Self { id: value.body_id, kind: value.kind, material: value.material }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 enum ContactTarget {
     Ground,
     Alphanumeric { id: u128 },
-    Character { id: u128, character_type: CharacterType }
+    Character { id: u128, character_type: CharacterType },
+    Segment { id: u128, owner: u128 }
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 struct Contact {
     body: ContactedBody,
-    magnitude: ContactMagnitude,
     target: ContactTarget
 }
 
@@ -125,48 +278,93 @@ impl Contact {
     //     Self { body, target, magnitude }
     // }
 
-    pub fn with_ground(body: ContactedBody, magnitude: ContactMagnitude) -> Self {
-        Self { body, magnitude, target: ContactTarget::Ground }
+    pub fn with_ground(body: ContactedBody) -> Self {
+        Self { body, target: ContactTarget::Ground }
     }
 
-    pub fn between_characters(body: ContactedBody, target: ContactedBody, magnitude: ContactMagnitude) -> Self {
-        Self { body, magnitude, target: ContactTarget::Character { id: target.body_id, character_type: target.character.unwrap() } }
+    pub fn between_characters(body: ContactedBody, target: ContactedBody) -> Self {
+        Self { body, target: ContactTarget::Character { id: target.body_id, character_type: target.character().unwrap() } }
     }
 
-    pub fn between_bodies(maybe_character_body: ContactedBody, target: ContactedBody, magnitude: ContactMagnitude) -> Self {
-        Self { body: maybe_character_body, magnitude, target: ContactTarget::Alphanumeric { id: target.body_id } }
+    pub fn between_bodies(maybe_character_body: ContactedBody, target: ContactedBody) -> Self {
+        let target = match target.kind {
+            BodyKind::Segment { owner } => ContactTarget::Segment { id: target.body_id, owner },
+            _ => ContactTarget::Alphanumeric { id: target.body_id }
+        };
+        Self { body: maybe_character_body, target }
     }
 }
 
 struct ContactListener {
-    heavy_collision_threshold: f32,
-    contacts: HashSet<Contact>
+    config: PhysicsConfig,
+    scale: PhysicsScale,
+    interactions: InteractionMatrix,
+    contacts: HashSet<Contact>,
+    fractures: HashSet<u128>,
+    collisions: Vec<Collision>,
+    /// characters in a heavy collision
+    stunned: HashSet<u128>,
+    /// screen coordinates of where each character touched each other body
+    contact_points: HashMap<(u128, u128), Point>,
+    /// characters that touched each other, either may eat the other
+    character_contacts: Vec<(BodyPtr<UserDataTypes>, BodyPtr<UserDataTypes>)>,
+    // token bucket limiting the rate of collision events
+    collision_tokens: f32
 }
 
 impl ContactListener {
-    fn new(heavy_collision_threshold: f32) -> Self {
-        Self { heavy_collision_threshold, contacts: HashSet::new() }
+    fn new(config: PhysicsConfig, scale: PhysicsScale) -> Self {
+        Self {
+            config,
+            scale,
+            interactions: InteractionMatrix::default(),
+            contacts: HashSet::new(),
+            fractures: HashSet::new(),
+            collisions: vec![],
+            stunned: HashSet::new(),
+            contact_points: HashMap::new(),
+            character_contacts: vec![],
+            collision_tokens: config.collision_events.burst
+        }
+    }
+
+    fn refill_collision_tokens(&mut self, delta: Duration) {
+        let limit = self.config.collision_events;
+        self.collision_tokens = (self.collision_tokens + limit.max_per_second * delta.as_secs_f32()).min(limit.burst);
+    }
+
+    fn heavy_collision_threshold(&self, body: Option<ContactedBody>) -> f32 {
+        body.map(|b| MaterialProperties::new(b.material, &self.config).heavy_collision_threshold())
+            .unwrap_or(self.config.heavy_collision_threshold)
     }
 
     fn contact_meta(body: Ref<B2body<UserDataTypes>>) -> Option<ContactedBody> {
-        if let Some(data) = body.get_user_data() {
-            Some(ContactedBody {
+        match body.get_user_data() {
+            // props are scenery, so contacts with them are treated like the ground
+            Some(data) if !matches!(data.body_type, BodyType::Prop) => Some(ContactedBody {
                 body_id: data.id,
-                character: if let BodyType::Character(character) = data.body_type {
-                    Some(character.character_type())
-                } else {
-                    None
-                },
-            })
-        } else {
-            None
+                material: data.material,
+                kind: BodyKind::from(&data.body_type),
+            }),
+            _ => None
         }
     }
 }
 
 impl B2contactListener<UserDataTypes> for ContactListener {
+    fn pre_solve(&mut self, contact: &mut dyn B2contactDynTrait<UserDataTypes>, _old_manifold: &B2manifold) {
+        let character_a = Self::contact_meta(contact.get_base().get_fixture_a().borrow().get_body().borrow())
+            .and_then(|meta| meta.character());
+        let character_b = Self::contact_meta(contact.get_base().get_fixture_b().borrow().get_body().borrow())
+            .and_then(|meta| meta.character());
+        if let (Some(a), Some(b)) = (character_a, character_b) {
+            if !self.interactions.collides(a, b) {
+                contact.get_base_mut().set_enabled(false);
+            }
+        }
+    }
 
-    fn post_solve(&mut self, contact: &mut dyn B2contactDynTrait<UserDataTypes>, _: &B2contactImpulse) {
+    fn post_solve(&mut self, contact: &mut dyn B2contactDynTrait<UserDataTypes>, impulse: &B2contactImpulse) {
         let base = contact.get_base();
 
         let mut world_manifold: B2worldManifold = Default::default();
@@ -185,12 +383,14 @@ impl B2contactListener<UserDataTypes> for ContactListener {
         let v_b = body_b.get_linear_velocity_from_world_point(world_manifold.points[0]);
         let contact_velocity = (v_a - v_b).length();
 
-        let contact_magnitude =
-            if contact_velocity > self.heavy_collision_threshold { ContactMagnitude::Heavy } else { ContactMagnitude::Light };
-
         let meta_a = Self::contact_meta(body_a);
         let meta_b = Self::contact_meta(body_b);
 
+        // the softest material involved decides how hard the collision must be
+        let heavy_collision_threshold = self.heavy_collision_threshold(meta_a)
+            .max(self.heavy_collision_threshold(meta_b));
+        let is_heavy = contact_velocity > heavy_collision_threshold;
+
         // if let Some(meta_a) = meta_a {
         //     self.contacts.insert(Contact::new(meta_a, meta_b, contact_magnitude));
         // }
@@ -199,27 +399,63 @@ impl B2contactListener<UserDataTypes> for ContactListener {
         //     self.contacts.insert(Contact::new(meta_b, meta_a, contact_magnitude));
         // }
 
+        if matches!(self.config.fracture, Some(fracture) if contact_velocity > fracture.impact_velocity) {
+            for meta in [meta_a, meta_b].into_iter().flatten() {
+                if meta.character().is_none() {
+                    self.fractures.insert(meta.body_id);
+                }
+            }
+        }
+
         if meta_a.is_none() && meta_b.is_none() {
             return;
         }
 
+        if is_heavy {
+            for meta in [meta_a, meta_b].into_iter().flatten() {
+                if meta.character().is_some() {
+                    self.stunned.insert(meta.body_id);
+                }
+            }
+        }
+
+        if is_heavy && self.collision_tokens >= 1.0 {
+            self.collision_tokens -= 1.0;
+            let (body, other) = match (meta_a, meta_b) {
+                (Some(a), b) => (a, b),
+                (None, b) => (b.unwrap(), None)
+            };
+            self.collisions.push(Collision {
+                point: self.scale.b2d_vec2_to_sdl(world_manifold.points[0]),
+                body: body.into(),
+                other: other.map(CollisionBody::from),
+                impulse: impulse.normal_impulses.iter()
+                    .take(impulse.count as usize)
+                    .copied()
+                    .fold(0.0, f32::max)
+            });
+        }
+
         if meta_a.is_none() || meta_b.is_none() {
             // collision with the ground
             let meta = [meta_a, meta_b].into_iter().find(|m| m.is_some()).unwrap().unwrap();
-            self.contacts.insert(Contact::with_ground(meta, contact_magnitude));
+            self.contacts.insert(Contact::with_ground(meta));
             return;
         }
 
         let meta_a = meta_a.unwrap();
         let meta_b = meta_b.unwrap();
-        if meta_a.character.is_some() && meta_b.character.is_some() {
+        if meta_a.character().is_some() && meta_b.character().is_some() {
             // collision between two characters
-            self.contacts.insert(Contact::between_characters(meta_a, meta_b, contact_magnitude));
-        } else if meta_b.character.is_some() {
+            self.contacts.insert(Contact::between_characters(meta_a, meta_b));
+            self.character_contacts.push((body_ptr_a.clone(), body_ptr_b.clone()));
+        } else if meta_b.character().is_some() {
             // prefer the character body to be the collision subject
-            self.contacts.insert(Contact::between_bodies(meta_b, meta_a, contact_magnitude));
+            self.contacts.insert(Contact::between_bodies(meta_b, meta_a));
+            self.contact_points.insert((meta_b.body_id, meta_a.body_id), self.scale.b2d_vec2_to_sdl(world_manifold.points[0]));
         } else {
-            self.contacts.insert(Contact::between_bodies(meta_a, meta_b, contact_magnitude));
+            self.contacts.insert(Contact::between_bodies(meta_a, meta_b));
+            self.contact_points.insert((meta_a.body_id, meta_b.body_id), self.scale.b2d_vec2_to_sdl(world_manifold.points[0]));
         }
     }
 }
@@ -229,11 +465,57 @@ impl Physics {
         let gravity = B2vec2::new(0.0, config.gravity);
         let mut world: B2worldPtr<UserDataTypes> = B2world::new(gravity);
 
-        let contact_listener = Rc::new(RefCell::new(ContactListener::new(config.heavy_collision_threshold)));
+        let contact_listener = Rc::new(RefCell::new(ContactListener::new(config, scale.clone())));
         world.borrow_mut().set_contact_listener(contact_listener.clone());
-        let (world_width, world_height) = scale.b2d_size();
 
         let ground = B2world::create_body(world.clone(), &B2bodyDef::default());
+        Self::create_walls(ground.clone(), &scale, config);
+
+        let level = match config.level {
+            LevelConfig::Empty => None,
+            LevelConfig::Fixed(name) => Some(name),
+            LevelConfig::Rotate { .. } => Some(LevelName::default())
+        };
+
+        let mut physics = Self {
+            world,
+            ground,
+            scale,
+            config,
+            rng: thread_rng(),
+            character_factory: CharacterFactory::new(config),
+            interactions: InteractionMatrix::default(),
+            contact_listener,
+            time_since_last_explosion: Duration::ZERO,
+            last_spawned: None,
+            chain_reaction: ChainReaction::default(),
+            pending_fractures: HashSet::new(),
+            pending_stuns: HashSet::new(),
+            chain_links: vec![],
+            chain_tail: None,
+            carried: vec![],
+            time_since_last_keystroke: Duration::ZERO,
+            time_still: Duration::ZERO,
+            settled: false,
+            level: None,
+            layout: Level::default(),
+            time_in_level: Duration::ZERO
+        };
+        if let Some(level) = level {
+            physics.load_level(level);
+        }
+        physics
+    }
+
+    /// how characters treat each other, defaults to the behaviour of each character
+    pub fn with_interactions(mut self, interactions: InteractionMatrix) -> Self {
+        self.contact_listener.borrow_mut().interactions = interactions.clone();
+        self.interactions = interactions;
+        self
+    }
+
+    fn create_walls(ground: BodyPtr<UserDataTypes>, scale: &PhysicsScale, config: PhysicsConfig) {
+        let (world_width, world_height) = scale.b2d_size();
 
         let shape = Rc::new(RefCell::new(B2edgeShape::default()));
         let fixture_def = B2fixtureDef {
@@ -262,16 +544,78 @@ impl Physics {
 
         // right
         shape.borrow_mut().set_two_sided(bottom_right, top_right);
-        B2body::create_fixture(ground.clone(), &fixture_def);
+        B2body::create_fixture(ground, &fixture_def);
+    }
 
-        Self {
-            world,
-            scale,
-            config,
-            rng: thread_rng(),
-            character_factory: CharacterFactory::new(config),
-            contact_listener,
-            time_since_last_explosion: Duration::ZERO
+    /// rebuilds the world around a new window size, keeping every body
+    pub fn resize(&mut self, scale: PhysicsScale) {
+        self.scale = scale;
+        self.contact_listener.borrow_mut().scale = self.scale.clone();
+
+        let walls = self.ground.borrow().get_fixture_list().iter().collect::<Vec<FixturePtr<UserDataTypes>>>();
+        for wall in walls {
+            B2body::destroy_fixture(self.ground.clone(), wall);
+        }
+        Self::create_walls(self.ground.clone(), &self.scale, self.config);
+
+        // props are placed relative to the world size so are rebuilt rather than moved
+        if self.level.is_some() {
+            self.build_level();
+        }
+
+        let (world_width, world_height) = self.scale.b2d_size();
+        for body in self.world.borrow().get_body_list().iter() {
+            let mut body = body.borrow_mut();
+            let data = match body.get_user_data() {
+                Some(data) if !matches!(data.body_type, BodyType::Prop) => data,
+                _ => continue
+            };
+            let position = body.get_position();
+            // keep the whole body inside the walls where it fits
+            let margin_x = (data.width / 2.0).min(world_width / 2.0);
+            let margin_y = (data.height / 2.0).min(world_height / 2.0);
+            let inside = B2vec2::new(
+                position.x.clamp(margin_x, world_width - margin_x),
+                position.y.clamp(margin_y, world_height - margin_y)
+            );
+            if inside.x != position.x || inside.y != position.y {
+                let angle = body.get_angle();
+                body.set_transform(inside, angle);
+                body.set_linear_velocity(B2vec2::zero());
+                body.set_awake(true);
+            }
+        }
+
+        if self.level.is_some() {
+            self.clear_props();
+        }
+    }
+
+    /// moves bodies the rebuilt props landed on somewhere clear of them
+    fn clear_props(&mut self) {
+        let mut in_props = vec![];
+        for body in self.world.borrow().get_body_list().iter() {
+            let data = match body.borrow().get_user_data() {
+                Some(data) if !matches!(data.body_type, BodyType::Prop) => data,
+                _ => continue
+            };
+            let position = body.borrow().get_position();
+            let aabb = B2AABB {
+                lower_bound: B2vec2::new(position.x - data.width / 2.0, position.y - data.height / 2.0),
+                upper_bound: B2vec2::new(position.x + data.width / 2.0, position.y + data.height / 2.0)
+            };
+            in_props.push((body, aabb, data.width, data.height));
+        }
+        for (body, aabb, width, height) in in_props {
+            if self.bodies_in_aabb(aabb).iter().any(is_prop) {
+                // anything else in the way is pushed aside by the simulation
+                let (position, _) = self.rng_spawn_position(width, height);
+                let mut body = body.borrow_mut();
+                let angle = body.get_angle();
+                body.set_transform(position, angle);
+                body.set_linear_velocity(B2vec2::zero());
+                body.set_awake(true);
+            }
         }
     }
 
@@ -286,14 +630,23 @@ impl Physics {
 
     pub fn update(&mut self, delta: Duration) -> Vec<GameEvent> {
         self.time_since_last_explosion += delta;
+        self.time_since_last_keystroke += delta;
+        self.contact_listener.borrow_mut().refill_collision_tokens(delta);
+        self.rotate_level(delta);
         self.world.borrow_mut().step(delta.as_secs_f32(), self.config.velocity_iterations, self.config.position_iterations);
+        self.snap_overloaded_links(delta);
 
         let mut to_destroy = HashSet::new();
         let mut character_reported_collisions = HashSet::new();
+        let mut character_meals: HashMap<u128, Vec<(u8, u8, u8)>> = HashMap::new();
+        let mut character_letters: HashMap<u128, Vec<char>> = HashMap::new();
+        let mut touching_self = HashSet::new();
+        let mut eaten_characters = HashSet::new();
+        let mut attacking = HashSet::new();
+        let contact_points = std::mem::take(&mut self.contact_listener.borrow_mut().contact_points);
         let mut events = vec![];
-        let mut is_heavy_collision = false;
         for contact in self.contact_listener.borrow_mut().contacts.drain() {
-            if let Some(character_subject) = contact.body.character {
+            if let Some(character_subject) = contact.body.character() {
                 // collision where a character is the subject
                 match contact.target {
                     ContactTarget::Ground => {
@@ -303,56 +656,134 @@ impl Physics {
                     ContactTarget::Alphanumeric { id } => {
                         if character_subject.destroys_on_collision() {
                             to_destroy.insert(id);
+                            let color = self.average_color(id);
+                            character_meals.entry(contact.body.body_id).or_default().push(color);
+                            if let Some(letter) = self.alphanumeric(id) {
+                                character_letters.entry(contact.body.body_id).or_default().push(letter);
+                            }
                             events.push(GameEvent::CharacterAttack(character_subject));
+                            if let Some(&point) = contact_points.get(&(contact.body.body_id, id)) {
+                                events.push(GameEvent::CharacterMunch { point, color });
+                            }
                         }
                     }
-                    ContactTarget::Character { id, .. } => {
-                        // report collision to both characters
-                        character_reported_collisions.insert(id);
+                    ContactTarget::Character { id, character_type } => {
+                        // friends bump into each other without turning away
+                        if self.interactions.get(character_subject, character_type) != Interaction::Befriend {
+                            character_reported_collisions.insert(contact.body.body_id);
+                        }
+                        if self.interactions.get(character_type, character_subject) != Interaction::Befriend {
+                            character_reported_collisions.insert(id);
+                        }
+                    }
+                    ContactTarget::Segment { id, owner } => {
                         character_reported_collisions.insert(contact.body.body_id);
+                        if owner == contact.body.body_id && self.segment_index(id).map_or(false, |index| index >= NECK_SEGMENTS) {
+                            touching_self.insert(owner);
+                        }
                     }
                 }
-            } else {
-                // collision between non-character bodies
-                if contact.magnitude == ContactMagnitude::Heavy && contact.body.character.is_none() {
-                    // a heavy collision between two alphanumerics or a alphanumeric and the ground
-                    is_heavy_collision = true;
+            }
+        }
+
+        let character_contacts = std::mem::take(&mut self.contact_listener.borrow_mut().character_contacts);
+        for (body_a, body_b) in character_contacts {
+            // only the two characters that met are copied out of their bodies
+            if let (Some((id_a, a)), Some((id_b, b))) = (character(&body_a), character(&body_b)) {
+                for (predator, predator_id, prey, prey_id) in [(&a, id_a, &b, id_b), (&b, id_b, &a, id_a)] {
+                    if predator.eats(prey, &self.interactions) && eaten_characters.insert(prey_id) {
+                        attacking.insert(predator_id);
+                        events.push(GameEvent::CharacterEats {
+                            predator: predator.character_type(),
+                            prey: prey.character_type()
+                        });
+                    }
                 }
             }
         }
 
-        if is_heavy_collision {
-            events.push(GameEvent::HeavyCollision);
+        events.extend(self.contact_listener.borrow_mut().collisions.drain(..).map(GameEvent::Collision));
+        let mut stunned = self.contact_listener.borrow_mut().stunned.drain().collect::<HashSet<u128>>();
+        stunned.extend(self.pending_stuns.drain());
+
+        let fracturing = self.contact_listener.borrow_mut().fractures.drain()
+            .chain(self.pending_fractures.drain())
+            .filter(|id| !to_destroy.contains(id))
+            .collect::<HashSet<u128>>();
+        for id in fracturing {
+            if let Some(body) = self.find_body(id) {
+                events.extend(self.fracture(body));
+            }
         }
 
-        let mut alphanumeric_positions = vec![];
+        let mut characters = HashSet::new();
+        let mut segments = vec![];
         for body_ptr in self.world.borrow().get_body_list().iter() {
-            let body = body_ptr.borrow_mut();
-            if let Some(BodyType::Alphanumeric(_)) = body.get_user_data().map(|d| d.body_type) {
-                alphanumeric_positions.push(body.get_position());
+            let mut body = body_ptr.borrow_mut();
+            if let Some(data) = body.get_user_data().as_mut() {
+                match &mut data.body_type {
+                    BodyType::Fragment(fragment) => {
+                        fragment.age += delta;
+                        let expired = fragment.age >= fragment.lifetime;
+                        body.set_user_data(data);
+                        if expired {
+                            to_destroy.insert(data.id);
+                        }
+                    }
+                    BodyType::Character(_) => {
+                        characters.insert(data.id);
+                    }
+                    BodyType::Segment(segment) => segments.push((data.id, segment.owner)),
+                    _ => {}
+                }
             }
         }
+        // segments go with the character they belong to
+        to_destroy.extend(segments.into_iter().filter(|(_, owner)| !characters.contains(owner)).map(|(id, _)| id));
+
+        let mut world_states = self.perceive(&character_reported_collisions, &mut character_meals, &touching_self);
 
         let mut to_transform = vec![];
+        let mut to_grow = vec![];
+        let mut to_grab = vec![];
+        let mut to_release = HashSet::new();
         for body_ptr in self.world.borrow().get_body_list().iter() {
             let mut body = body_ptr.borrow_mut();
             if let Some(data) = body.get_user_data().as_mut() {
                 if let BodyType::Character(character) = &mut data.body_type {
-                    let is_colliding = character_reported_collisions.contains(&data.id);
-                    let position = body.get_position();
-                    let distance_to_closest_body = alphanumeric_positions.iter()
-                        .map(|&v| {
-                            let distance = position - v;
-                            (distance, distance.length())
-                        })
-                        .filter(|(v, d)| *d > 0.0001 || *d < 0.0001) // exclude self
-                        .min_by(|(v1, d1), (v2, d2)| (*d1).total_cmp(d2))
-                        .map(|(v1, _)| v1);
-
-                    let character_world_state = CharacterWorldState::new(is_colliding, distance_to_closest_body);
+                    if eaten_characters.contains(&data.id) {
+                        character.destroy();
+                    }
+                    if attacking.contains(&data.id) {
+                        character.attack();
+                    }
+                    if stunned.contains(&data.id) {
+                        character.stun();
+                    }
+                    let character_world_state = world_states.remove(&data.id)
+                        .unwrap_or_else(|| CharacterWorldState::new(false, None, vec![]))
+                        .with_letters(character_letters.remove(&data.id).unwrap_or_default());
                     let character_physics = self.character_factory.update(character, delta, character_world_state);
+                    if character_physics.dies() {
+                        character.destroy();
+                    }
+                    if character_physics.powers_up() {
+                        events.push(GameEvent::CharacterPoweredUp(character.character_type()));
+                    }
+                    for (from, to) in character.take_transitions() {
+                        events.push(GameEvent::CharacterStateChanged { id: data.id, character: character.character_type(), from, to });
+                    }
+                    for &color in character_physics.growth() {
+                        to_grow.push((data.id, character.character_type(), color));
+                    }
+                    if let Some(id) = character_physics.grab() {
+                        to_grab.push((data.id, id));
+                    }
                     if !character.state().is_alive() {
                         to_destroy.insert(data.id);
+                        to_release.insert(data.id);
+                    } else if character_physics.releases() {
+                        to_release.insert(data.id);
                     }
 
                     body.set_linear_velocity(character_physics.velocity());
@@ -366,171 +797,746 @@ impl Physics {
             body.borrow_mut().set_transform(position, angle);
         }
 
+        for (owner, character_type, color) in to_grow {
+            self.grow_segment(owner, character_type, color);
+        }
+
+        for character in to_release {
+            self.release(character);
+        }
+        for (character, id) in to_grab {
+            self.grab(character, id);
+        }
+
+        events.extend(self.update_pending_explosions(delta));
+        events.extend(self.update_settled(delta));
 
         self.destroy_bodies_by_id(to_destroy).into_iter().chain(events).collect()
     }
 
-    pub fn action(&mut self, action: PhysicsAction) -> Vec<GameEvent> {
-        let mut result = vec![];
-        match action {
-            PhysicsAction::Push(direction) => {
-                let magnitude = self.config.push_force_magnitude;
-                let force = match direction {
-                    Direction::Up => B2vec2::new(0.0, -magnitude),
-                    Direction::Down => B2vec2::new(0.0, magnitude),
-                    Direction::Left => B2vec2::new(-magnitude, 0.0),
-                    Direction::Right => B2vec2::new(magnitude, 0.0),
-                };
-
-                let world = self.world.borrow_mut();
-                for body in world.get_body_list().iter() {
-                    let mut body = body.borrow_mut();
-                    if body.get_type() == B2bodyType::B2DynamicBody {
-                        body.apply_force_to_center(force, true);
-                    }
-                }
-            }
-            PhysicsAction::Explode => {
-                // the simulation falls apart if too many explosions are spammed
-                if self.time_since_last_explosion < Duration::from_millis(100) {
-                    return result;
+    /// what each character can see of the bodies around it
+    fn perceive(
+        &self,
+        colliding: &HashSet<u128>,
+        meals: &mut HashMap<u128, Vec<(u8, u8, u8)>>,
+        touching_self: &HashSet<u128>
+    ) -> HashMap<u128, CharacterWorldState> {
+        let perception = self.config.perception;
+        let mut characters = vec![];
+        let mut minds = HashMap::new();
+        let mut perceivable = vec![];
+        for body in self.world.borrow().get_body_list().iter() {
+            let body = body.borrow();
+            if let Some(data) = body.get_user_data() {
+                let kind = BodyKind::from(&data.body_type);
+                if kind == BodyKind::Unknown {
+                    continue;
                 }
-                self.time_since_last_explosion = Duration::ZERO;
-
-                let location = self.rng_world_coordinates(0.1, 0.1).get_center();
-
-                let world = self.world.borrow_mut();
-                for body in world.get_body_list().iter() {
-                    let mut body = body.borrow_mut();
-
-                    if body.get_type() != B2bodyType::B2DynamicBody {
-                        continue;
-                    }
-
-                    let mut vector = body.get_position() - location;
-                    let distance = vector.normalize();
-                    // force is inversely proportional to the distance between body and explosion center
-                    let force = self.config.explosion_force_magnitude - (distance / self.config.explosion_distance) * self.config.explosion_force_magnitude;
-                    if force > 0.0 {
-                        vector *= force;
-                        body.apply_force_to_center(vector, true);
-                    }
+                if let BodyType::Character(character) = &data.body_type {
+                    characters.push((data.id, body.get_position(), body.get_linear_velocity()));
+                    minds.insert(data.id, character.clone());
                 }
-
-                let point = self.scale.b2d_vec2_to_sdl(location);
-                result.push(GameEvent::Explosion { x: point.x(), y: point.y() });
+                perceivable.push(Perceivable {
+                    id: data.id,
+                    kind,
+                    position: body.get_position(),
+                    velocity: body.get_linear_velocity()
+                });
             }
         }
-        result
+        let grid = PerceptionGrid::new(perception.cell_size, perceivable);
+
+        characters.into_iter()
+            .map(|(id, position, velocity)| {
+                let perceive = |(body, distance): (Perceivable, f32)| PerceivedBody {
+                    id: body.id,
+                    kind: body.kind,
+                    offset: body.position - position,
+                    velocity: body.velocity,
+                    distance,
+                    visible: !perception.line_of_sight || self.in_line_of_sight(id, position, body.id, body.position)
+                };
+                let within_radius = |(_, distance): &(Perceivable, f32)| *distance <= perception.radius;
+                // a character's own segments are part of it rather than something nearby
+                let nearby = grid.k_nearest(position, perception.neighbours, id, |b| b.kind != BodyKind::Segment { owner: id })
+                    .into_iter()
+                    .filter(within_radius)
+                    .map(perceive)
+                    .collect();
+                let other_characters = grid.k_nearest(position, perception.neighbours, id, |b| matches!(b.kind, BodyKind::Character(_)))
+                    .into_iter()
+                    .filter(within_radius)
+                    .map(perceive)
+                    .collect::<Vec<PerceivedBody>>();
+                let threats = other_characters.iter()
+                    .filter(|other| match (minds.get(&id), minds.get(&other.id)) {
+                        (Some(me), Some(them)) => them.eats(me, &self.interactions)
+                            || self.interactions.get(me.character_type(), them.character_type()) == Interaction::Flee,
+                        _ => false
+                    })
+                    .copied()
+                    .collect();
+                // pointing from the food to the character
+                let closest_body = grid.k_nearest(position, 1, id, |b| b.kind == BodyKind::Alphanumeric)
+                    .first()
+                    .map(|(body, _)| position - body.position);
+                let world_state = CharacterWorldState::new(colliding.contains(&id), closest_body, nearby)
+                    .with_characters(other_characters)
+                    .with_meals(meals.remove(&id).unwrap_or_default())
+                    .with_touching_self(touching_self.contains(&id))
+                    .with_carrying(self.carrying(id))
+                    .with_threats(threats)
+                    .with_velocity(velocity)
+                    .with_wall(Some(self.closest_wall(position)));
+                (id, world_state)
+            })
+            .collect()
     }
 
-    fn rng_world_coordinates(&mut self, width: f32, height: f32) -> B2AABB {
-        const SPAWN_BUFFER: f32 = 0.1;
-        let hw = width / 2.0 + SPAWN_BUFFER;
-        let hh = height / 2.0 + SPAWN_BUFFER;
-        let (world_width, world_height) = self.scale.b2d_size();
-        let x = self.rng.gen_range(hw .. (world_width - hw));
-        let y = self.rng.gen_range(hh .. (world_height - hh));
-        B2AABB {
-            lower_bound: B2vec2::new(x - hw, y - hh),
-            upper_bound: B2vec2::new(x + hw, y + hh)
-        }
+    /// offset from the position to the closest point on the nearest wall
+    fn closest_wall(&self, position: B2vec2) -> B2vec2 {
+        let (width, height) = self.scale.b2d_size();
+        [
+            B2vec2::new(-position.x, 0.0),
+            B2vec2::new(width - position.x, 0.0),
+            B2vec2::new(0.0, -position.y),
+            B2vec2::new(0.0, height - position.y)
+        ].into_iter()
+            .min_by(|a, b| a.length().total_cmp(&b.length()))
+            .unwrap()
     }
 
-    fn rng_spawn_position_attempt(&mut self, width: f32, height: f32) -> (B2AABB, Vec<BodyPtr<UserDataTypes>>) {
-        let aabb = self.rng_world_coordinates(width, height);
-        let world = self.world.borrow();
-
-        let mut to_destroy_set = HashSet::new();
-        world.query_aabb(|f: FixturePtr<UserDataTypes>| {
-            if let Some(data) = f.borrow().get_body().borrow().get_user_data() {
-                to_destroy_set.insert(data.id);
+    /// nothing but the target is hit by a ray from the viewer to the target
+    fn in_line_of_sight(&self, viewer: u128, from: B2vec2, target: u128, to: B2vec2) -> bool {
+        if (to - from).length_squared() < f32::EPSILON {
+            return true;
+        }
+        let mut hit = None;
+        self.world.borrow().ray_cast(|f: FixturePtr<UserDataTypes>, _: B2vec2, _: B2vec2, fraction: f32| {
+            match f.borrow().get_body().borrow().get_user_data() {
+                Some(data) if data.id != viewer => {
+                    hit = Some(data.id);
+                    fraction
+                }
+                _ => -1.0
             }
-            true
-        }, aabb);
+        }, from, to);
+        hit.map_or(true, |id| id == target)
+    }
 
-        let mut to_destroy = vec![];
-        for body in world.get_body_list().iter() {
-            if matches!(body.borrow().get_user_data(), Some(data) if to_destroy_set.contains(&data.id)) {
-                to_destroy.push(body)
+    fn rotate_level(&mut self, delta: Duration) {
+        if let LevelConfig::Rotate { every_ms } = self.config.level {
+            self.time_in_level += delta;
+            if self.time_in_level >= Duration::from_millis(every_ms) {
+                let next = self.level.unwrap_or_default().into_next();
+                self.load_level(next);
             }
         }
+    }
 
-        (aabb, to_destroy)
+    /// replaces the props in the world with those of the level
+    fn load_level(&mut self, name: LevelName) {
+        self.layout = Self::layout(name);
+        self.build_level();
+        self.level = Some(name);
+        self.time_in_level = Duration::ZERO;
     }
 
-    fn rng_spawn_position(&mut self, width: f32, height: f32) -> (B2vec2, Vec<BodyPtr<UserDataTypes>>) {
-        let mut attempt = 0;
-        loop {
-            let (aabb, to_destroy) = self.rng_spawn_position_attempt(width, height);
-            if to_destroy.is_empty() || attempt >= 10 {
-                return (aabb.get_center(), to_destroy)
+    /// a level that fails to load is played without props rather than ending the game
+    fn layout(name: LevelName) -> Level {
+        name.load().unwrap_or_else(|e| {
+            println!("Cannot load level {}, {}, playing without props", name.name(), e);
+            Level::default()
+        })
+    }
+
+    /// replaces the props in the world with those of the current layout
+    fn build_level(&mut self) {
+        {
+            let mut world = self.world.borrow_mut();
+            let props = world.get_body_list().iter()
+                .filter(|body| matches!(body.borrow().get_user_data(), Some(data) if matches!(data.body_type, BodyType::Prop)))
+                .collect::<Vec<BodyPtr<UserDataTypes>>>();
+            for body in props {
+                world.destroy_body(body);
             }
-            attempt += 1;
         }
-    }
 
-    pub fn spawn_character(&mut self, character_type: CharacterType) -> Vec<GameEvent> {
-        let shape = self.character_factory.shape(character_type);
-        let mut aabb = B2AABB::default();
-        shape.borrow().compute_aabb(&mut aabb, B2Transform::default(), 0);
+        let layout = std::mem::take(&mut self.layout);
+        for prop in layout.props() {
+            self.spawn_prop(prop);
+        }
+        self.layout = layout;
+    }
 
-        let width = aabb.upper_bound.x - aabb.lower_bound.x;
-        let height = aabb.upper_bound.y - aabb.lower_bound.y;
+    fn spawn_prop(&mut self, prop: &Prop) {
+        let (world_width, world_height) = self.scale.b2d_size();
+        let (x, y) = prop.position();
+        let position = B2vec2::new(x * world_width, y * world_height);
+        let triangles = prop.triangles().into_iter()
+            .map(|triangle| triangle.map(|(x, y)| B2vec2::new(x * world_height, y * world_height)))
+            .collect::<Vec<[B2vec2; 3]>>();
+
+        let mut lower = B2vec2::zero();
+        let mut upper = B2vec2::zero();
+        for vertex in triangles.iter().flat_map(|t| t.iter()) {
+            lower = B2vec2::new(lower.x.min(vertex.x), lower.y.min(vertex.y));
+            upper = B2vec2::new(upper.x.max(vertex.x), upper.y.max(vertex.y));
+        }
 
-        let (position, to_destroy) = self.rng_spawn_position(width, height);
-        let mut events = self.destroy_bodies(to_destroy);
+        let (body_type, angular_velocity) = match prop.motion() {
+            PropMotion::Static => (B2bodyType::B2StaticBody, 0.0),
+            PropMotion::Kinematic { angular_velocity } => (B2bodyType::B2KinematicBody, angular_velocity.to_radians()),
+            PropMotion::Pivot => (B2bodyType::B2DynamicBody, 0.0)
+        };
 
-        let character = self.character_factory.new_character(character_type);
+        let properties = MaterialProperties::new(prop.material(), &self.config);
         let body_data = BodyData {
             id: self.rng.gen(),
-            width,
-            height,
-            body_type: BodyType::Character(character)
+            width: upper.x - lower.x,
+            height: upper.y - lower.y,
+            material: prop.material(),
+            body_type: BodyType::Prop,
+            shape: BodyShape::default()
         };
         let body_def = B2bodyDef {
             position,
-            body_type: B2bodyType::B2DynamicBody,
+            angle: prop.angle().to_radians(),
+            angular_velocity,
+            body_type,
+            gravity_scale: properties.gravity_scale(),
             user_data: Some(body_data),
             ..B2bodyDef::default()
         };
         let body = B2world::create_body(self.world.clone(), &body_def);
 
-        let fixture_def = B2fixtureDef {
-            shape: Some(shape),
-            density: self.config.body_density,
-            friction: self.config.body_friction,
-            restitution: self.config.body_restitution,
+        let (r, g, b) = prop.color();
+        let shape = Rc::new(RefCell::new(B2polygonShape::default()));
+        let mut fixture_def = B2fixtureDef {
+            shape: Some(shape.clone()),
+            density: properties.density(),
+            friction: properties.friction(),
+            restitution: properties.restitution(),
             ..B2fixtureDef::default()
         };
-        B2body::create_fixture(body.clone(), &fixture_def);
+        for points in triangles {
+            shape.borrow_mut().set(&points);
+            fixture_def.user_data = Some(FixtureData { id: self.rng.gen(), color: Color::RGB(r, g, b) });
+            B2body::create_fixture(body.clone(), &fixture_def);
+        }
+        self.attach_shape(&body);
 
-        let body = self.body(body).unwrap();
-        events.push(GameEvent::Spawned(body));
-        events
+        if prop.motion() == PropMotion::Pivot {
+            let mut def = B2revoluteJointDef::default();
+            def.initialize(self.ground.clone(), body, position);
+            def.base.user_data = Some(JointData { id: self.rng.gen() });
+            self.world.borrow_mut().create_joint(&B2JointDefEnum::RevoluteJoint(def));
+        }
     }
 
-    pub fn spawn_asset(&mut self, sprite: SpriteAsset) -> Vec<GameEvent> {
-        let polygon_scale = self.config.polygon_scale;
+    /// tracks how long every dynamic body has been asleep or slower than the settle threshold
+    fn update_settled(&mut self, delta: Duration) -> Option<GameEvent> {
+        let settle = self.config.settle?;
+        let is_still = self.world.borrow().get_body_list().iter()
+            .filter(|body| body.borrow().get_type() == B2bodyType::B2DynamicBody)
+            .all(|body| {
+                let body = body.borrow();
+                !body.is_awake() || body.get_linear_velocity().length() < settle.velocity_threshold
+            });
+
+        if !is_still {
+            self.time_still = Duration::ZERO;
+            if self.settled {
+                self.settled = false;
+                return Some(GameEvent::WorldWoke);
+            }
+            return None;
+        }
 
-        let (sprite_width, sprite_height) = sprite.unit_scale();
-        let width = sprite_width as f32 * polygon_scale;
-        let height = sprite_height as f32 * polygon_scale;
+        self.time_still += delta;
+        if !self.settled && self.time_still >= Duration::from_millis(settle.settle_ms) {
+            self.settled = true;
+            return Some(GameEvent::WorldSettled);
+        }
+        None
+    }
+
+    /// snaps word chain links pulled harder than the configured break force
+    fn snap_overloaded_links(&mut self, delta: Duration) {
+        let break_force = match self.config.word_chain {
+            Some(word_chain) if !delta.is_zero() => word_chain.break_force,
+            _ => return
+        };
+        let inv_dt = 1.0 / delta.as_secs_f32();
+        let overloaded = self.chain_links.iter()
+            .filter(|link| link.joint.borrow().get_reaction_force(inv_dt).length() > break_force)
+            .map(|link| link.id)
+            .collect::<HashSet<u128>>();
+        self.break_links(|link| overloaded.contains(&link.id));
+    }
+
+    fn break_links<F: Fn(&ChainLink) -> bool>(&mut self, predicate: F) {
+        let mut world = self.world.borrow_mut();
+        self.chain_links.retain(|link| {
+            if predicate(link) {
+                world.destroy_joint(link.joint.clone());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn update_pending_explosions(&mut self, delta: Duration) -> Vec<GameEvent> {
+        let mut events = vec![];
+        for pending in self.chain_reaction.update(delta) {
+            if let Some(body) = self.find_body(pending.body_id) {
+                // the body becomes the explosion
+                let location = body.borrow().get_position();
+                events.extend(self.destroy_bodies(vec![body]));
+                events.extend(self.explode(location, pending.strength, pending.depth));
+            }
+        }
+        events
+    }
+
+    pub fn action(&mut self, action: PhysicsAction) -> Vec<GameEvent> {
+        let mut result = vec![];
+        match action {
+            PhysicsAction::Push(direction) => {
+                let magnitude = self.config.push_force_magnitude;
+                let force = match direction {
+                    Direction::Up => B2vec2::new(0.0, -magnitude),
+                    Direction::Down => B2vec2::new(0.0, magnitude),
+                    Direction::Left => B2vec2::new(-magnitude, 0.0),
+                    Direction::Right => B2vec2::new(magnitude, 0.0),
+                };
+
+                let world = self.world.borrow_mut();
+                for body in world.get_body_list().iter() {
+                    let mut body = body.borrow_mut();
+                    if body.get_type() == B2bodyType::B2DynamicBody {
+                        body.apply_force_to_center(force, true);
+                    }
+                }
+            }
+            PhysicsAction::Explode(target) => {
+                // the simulation falls apart if too many explosions are spammed
+                if self.time_since_last_explosion < Duration::from_millis(100) {
+                    return result;
+                }
+                self.time_since_last_explosion = Duration::ZERO;
+
+                let location = self.explosion_location(target);
+                result.extend(self.explode(location, 1.0, 0));
+            }
+        }
+        result
+    }
+
+    fn explosion_location(&mut self, target: ExplosionTarget) -> B2vec2 {
+        let location = match target {
+            ExplosionTarget::Random => None,
+            ExplosionTarget::Point { x, y } => Some(self.scale.point_to_b2d_vec2(Point::new(x, y))),
+            ExplosionTarget::LastSpawn => self.last_spawned
+                .and_then(|id| self.find_body(id))
+                .map(|body| body.borrow().get_position()),
+            ExplosionTarget::Densest => self.densest_location(),
+            ExplosionTarget::Character => {
+                let characters = self.world.borrow().get_body_list().iter()
+                    .filter(|body| matches!(
+                        body.borrow().get_user_data().map(|d| d.body_type),
+                        Some(BodyType::Character(character)) if character.state().is_alive()
+                    ))
+                    .map(|body| body.borrow().get_position())
+                    .collect::<Vec<B2vec2>>();
+                characters.choose(&mut self.rng).copied()
+            }
+        };
+        // fall back to anywhere when the target does not exist
+        location.unwrap_or_else(|| self.rng_world_coordinates(0.1, 0.1).get_center())
+    }
+
+    /// the average position of bodies in the most crowded cell of a coarse grid over the world
+    fn densest_location(&self) -> Option<B2vec2> {
+        let positions = self.world.borrow().get_body_list().iter()
+            .filter(|body| body.borrow().get_type() == B2bodyType::B2DynamicBody)
+            .map(|body| body.borrow().get_position())
+            .collect::<Vec<B2vec2>>();
+        explosion::densest_location(positions.into_iter(), self.config.polygon_scale * 2.0)
+    }
+
+    /// applies an explosive force to all dynamic bodies around the location,
+    /// strength is relative to the configured explosion force & distance
+    fn explode(&mut self, location: B2vec2, strength: f32, depth: u32) -> Vec<GameEvent> {
+        let force_magnitude = self.config.explosion_force_magnitude * strength;
+        let distance_magnitude = self.config.explosion_distance * strength;
+        let chain = self.config.explosion_chain;
+        let fracture = self.config.fracture;
+
+        let mut chained = vec![];
+        let mut fractured = vec![];
+        let mut stunned = vec![];
+        let mut caught = HashSet::new();
+        {
+            let world = self.world.borrow_mut();
+            for body in world.get_body_list().iter() {
+                let mut body = body.borrow_mut();
+
+                if body.get_type() != B2bodyType::B2DynamicBody {
+                    continue;
+                }
+
+                let mut vector = body.get_position() - location;
+                let distance = vector.normalize();
+                let force = explosion::force(force_magnitude, distance_magnitude, distance);
+                if force > 0.0 {
+                    vector *= force;
+                    body.apply_force_to_center(vector, true);
+
+                    if let Some(data) = body.get_user_data() {
+                        caught.insert(data.id);
+
+                        if matches!(data.body_type, BodyType::Character(_)) {
+                            stunned.push(data.id);
+                        }
+                        if matches!(data.body_type, BodyType::Alphanumeric(_)) {
+                            if matches!(fracture, Some(fracture) if force > fracture.explosion_force) {
+                                // letters closest to the explosion are blown to pieces
+                                fractured.push(data.id);
+                            } else if chain.is_some() {
+                                chained.push((data.id, force));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(chain) = chain {
+            for (id, force) in chained {
+                self.chain_reaction.catch(chain, id, force, strength, depth);
+            }
+        }
+
+        // fractured after the next step so the pieces inherit the explosion's velocity
+        self.pending_fractures.extend(fractured);
+        self.pending_stuns.extend(stunned);
+
+        if matches!(self.config.word_chain, Some(word_chain) if word_chain.break_on_explosion) {
+            self.break_links(|link| caught.contains(&link.body_a) || caught.contains(&link.body_b));
+        }
+
+        let point = self.scale.b2d_vec2_to_sdl(location);
+        vec![GameEvent::Explosion {
+            x: point.x(),
+            y: point.y(),
+            radius: self.scale.b2d_to_sdl(distance_magnitude).max(0) as u32,
+            strength
+        }]
+    }
+
+    /// average color of the triangles of a body
+    fn average_color(&self, id: u128) -> (u8, u8, u8) {
+        let shape = self.find_body(id)
+            .and_then(|body| body.borrow().get_user_data())
+            .map(|data| data.shape)
+            .unwrap_or_default();
+        if shape.is_empty() {
+            return (0xFF, 0xFF, 0xFF);
+        }
+        let (r, g, b) = shape.iter()
+            .fold((0u32, 0u32, 0u32), |(r, g, b), t| (r + t.color.r as u32, g + t.color.g as u32, b + t.color.b as u32));
+        let count = shape.len() as u32;
+        ((r / count) as u8, (g / count) as u8, (b / count) as u8)
+    }
+
+    /// a filled polygon approximating a circle, for drawing round bodies
+    fn disc(&self, radius: f32, (r, g, b): (u8, u8, u8)) -> BodyShape {
+        const SIDES: usize = 12;
+        let (radius, _) = self.scale.b2d_vec2_to_sdl_f32(B2vec2::new(radius, 0.0));
+        let rim = (0..SIDES)
+            .map(|i| (i as f32 * std::f32::consts::TAU / SIDES as f32).sin_cos())
+            .map(|(sin, cos)| (radius * cos, radius * sin))
+            .collect::<Vec<(f32, f32)>>();
+        let triangles = (0..SIDES)
+            .map(|i| LocalTriangle { points: [(0.0, 0.0), rim[i], rim[(i + 1) % SIDES]], color: Color::RGB(r, g, b) })
+            .collect();
+        Arc::new(triangles)
+    }
+
+    /// adds a segment to the end of a character made from more than one body
+    fn grow_segment(&mut self, owner: u128, character_type: CharacterType, color: (u8, u8, u8)) {
+        let head = match self.find_body(owner) {
+            Some(head) => head,
+            None => return
+        };
+        let (tail, index) = self.segments(owner).into_iter()
+            .last()
+            .map(|(body, segment)| (body, segment.index + 1))
+            .unwrap_or((head.clone(), 0));
+
+        let shape = self.character_factory.shape(character_type);
+        let mut aabb = B2AABB::default();
+        shape.borrow().compute_aabb(&mut aabb, B2Transform::default(), 0);
+        let width = aabb.upper_bound.x - aabb.lower_bound.x;
+        let height = aabb.upper_bound.y - aabb.lower_bound.y;
+
+        // a little further along the line from the head through the tail
+        let head_position = head.borrow().get_position();
+        let tail_position = tail.borrow().get_position();
+        let mut away = if index == 0 {
+            B2vec2::zero() - head.borrow().get_linear_velocity()
+        } else {
+            tail_position - head_position
+        };
+        if away.normalize() < f32::EPSILON {
+            away = B2vec2::new(0.0, -1.0);
+        }
+        away *= width;
+        let position = tail_position + away;
+
+        let material = character_type.material();
+        let properties = MaterialProperties::new(material, &self.config);
+        let body_data = BodyData {
+            id: self.rng.gen(),
+            width,
+            height,
+            material,
+            body_type: BodyType::Segment(SegmentBody { owner, character_type, index, color }),
+            shape: self.disc(width / 2.0, color)
+        };
+        let body_def = B2bodyDef {
+            position,
+            body_type: B2bodyType::B2DynamicBody,
+            gravity_scale: properties.gravity_scale() * character_type.gravity_scale(),
+            user_data: Some(body_data),
+            ..B2bodyDef::default()
+        };
+        let body = B2world::create_body(self.world.clone(), &body_def);
+        let fixture_def = B2fixtureDef {
+            shape: Some(shape),
+            density: properties.density(),
+            friction: properties.friction(),
+            restitution: properties.restitution(),
+            ..B2fixtureDef::default()
+        };
+        B2body::create_fixture(body.clone(), &fixture_def);
+        self.join_segment(tail, body);
+    }
+
+    /// segments of a character in order from the head
+    fn segments(&self, owner: u128) -> Vec<(BodyPtr<UserDataTypes>, SegmentBody)> {
+        let mut segments = self.world.borrow().get_body_list().iter()
+            .filter_map(|body| match body.borrow().get_user_data()?.body_type {
+                BodyType::Segment(segment) if segment.owner == owner => Some((body.clone(), segment)),
+                _ => None
+            })
+            .collect::<Vec<(BodyPtr<UserDataTypes>, SegmentBody)>>();
+        segments.sort_by_key(|(_, segment)| segment.index);
+        segments
+    }
+
+    fn segment_index(&self, id: u128) -> Option<usize> {
+        match self.find_body(id)?.borrow().get_user_data()?.body_type {
+            BodyType::Segment(segment) => Some(segment.index),
+            _ => None
+        }
+    }
+
+    fn join_segment(&mut self, front: BodyPtr<UserDataTypes>, segment: BodyPtr<UserDataTypes>) {
+        let mut anchor = front.borrow().get_position() + segment.borrow().get_position();
+        anchor *= 0.5;
+        let mut def = B2revoluteJointDef::default();
+        def.initialize(front, segment, anchor);
+        def.base.user_data = Some(JointData { id: self.rng.gen() });
+        self.world.borrow_mut().create_joint(&B2JointDefEnum::RevoluteJoint(def));
+    }
+
+    /// weld a letter to a character so it is carried around
+    fn grab(&mut self, character: u128, id: u128) {
+        if self.carried.iter().any(|c| c.character == character || c.body == id) {
+            return;
+        }
+        if let (Some(character_ptr), Some(body_ptr)) = (self.find_body(character), self.find_body(id)) {
+            if !matches!(body_ptr.borrow().get_user_data().map(|d| d.body_type), Some(BodyType::Alphanumeric(_))) {
+                return;
+            }
+            let anchor = body_ptr.borrow().get_position();
+            let mut def = B2weldJointDef::default();
+            def.initialize(character_ptr, body_ptr, anchor);
+            def.base.user_data = Some(JointData { id: self.rng.gen() });
+            let joint = self.world.borrow_mut().create_joint(&B2JointDefEnum::WeldJoint(def));
+            self.carried.push(CarriedBody { character, body: id, joint });
+        }
+    }
+
+    /// drop whatever the character is carrying
+    fn release(&mut self, character: u128) {
+        let mut world = self.world.borrow_mut();
+        self.carried.retain(|carried| {
+            if carried.character == character {
+                world.destroy_joint(carried.joint.clone());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn carrying(&self, character: u128) -> Option<u128> {
+        self.carried.iter().find(|c| c.character == character).map(|c| c.body)
+    }
+
+    /// every character by id
+    fn alphanumeric(&self, id: u128) -> Option<char> {
+        match self.find_body(id)?.borrow().get_user_data()?.body_type {
+            BodyType::Alphanumeric(alphanumeric) => Some(alphanumeric.alphanumeric),
+            _ => None
+        }
+    }
+
+    fn find_body(&self, id: u128) -> Option<BodyPtr<UserDataTypes>> {
+        self.world.borrow().get_body_list().iter()
+            .find(|body| matches!(body.borrow().get_user_data(), Some(data) if data.id == id))
+    }
+
+    fn rng_world_coordinates(&mut self, width: f32, height: f32) -> B2AABB {
+        const SPAWN_BUFFER: f32 = 0.1;
+        let hw = width / 2.0 + SPAWN_BUFFER;
+        let hh = height / 2.0 + SPAWN_BUFFER;
+        let (world_width, world_height) = self.scale.b2d_size();
+        let x = self.rng.gen_range(hw .. (world_width - hw));
+        let y = self.rng.gen_range(hh .. (world_height - hh));
+        B2AABB {
+            lower_bound: B2vec2::new(x - hw, y - hh),
+            upper_bound: B2vec2::new(x + hw, y + hh)
+        }
+    }
+
+    /// none if the spot overlaps a prop, props are part of the level so are never cleared out of the way
+    fn rng_spawn_position_attempt(&mut self, width: f32, height: f32) -> Option<(B2AABB, Vec<BodyPtr<UserDataTypes>>)> {
+        let aabb = self.rng_world_coordinates(width, height);
+        let (props, to_destroy): (Vec<_>, Vec<_>) = self.bodies_in_aabb(aabb).into_iter().partition(is_prop);
+        if props.is_empty() {
+            Some((aabb, to_destroy))
+        } else {
+            None
+        }
+    }
+
+    fn bodies_in_aabb(&self, aabb: B2AABB) -> Vec<BodyPtr<UserDataTypes>> {
+        let world = self.world.borrow();
+
+        let mut ids = HashSet::new();
+        world.query_aabb(|f: FixturePtr<UserDataTypes>| {
+            if let Some(data) = f.borrow().get_body().borrow().get_user_data() {
+                ids.insert(data.id);
+            }
+            true
+        }, aabb);
+
+        let mut bodies = vec![];
+        for body in world.get_body_list().iter() {
+            if matches!(body.borrow().get_user_data(), Some(data) if ids.contains(&data.id)) {
+                bodies.push(body)
+            }
+        }
+        bodies
+    }
+
+    fn rng_spawn_position(&mut self, width: f32, height: f32) -> (B2vec2, Vec<BodyPtr<UserDataTypes>>) {
+        let mut clear_of_props = 0;
+        for _ in 0..SPAWN_ATTEMPTS {
+            if let Some((aabb, to_destroy)) = self.rng_spawn_position_attempt(width, height) {
+                if to_destroy.is_empty() || clear_of_props >= CLEAR_SPAWN_ATTEMPTS {
+                    return (aabb.get_center(), to_destroy);
+                }
+                clear_of_props += 1;
+            }
+        }
+        (self.rng_world_coordinates(width, height).get_center(), vec![])
+    }
+
+    pub fn spawn_character(&mut self, character_type: CharacterType) -> Vec<GameEvent> {
+        let shape = self.character_factory.shape(character_type);
+        let mut aabb = B2AABB::default();
+        shape.borrow().compute_aabb(&mut aabb, B2Transform::default(), 0);
+
+        let width = aabb.upper_bound.x - aabb.lower_bound.x;
+        let height = aabb.upper_bound.y - aabb.lower_bound.y;
 
         let (position, to_destroy) = self.rng_spawn_position(width, height);
         let mut events = self.destroy_bodies(to_destroy);
 
+        let character = self.character_factory.new_character(character_type);
+        let material = character_type.material();
+        let properties = MaterialProperties::new(material, &self.config);
+        let id = self.rng.gen();
+        self.last_spawned = Some(id);
         let body_data = BodyData {
-            id: self.rng.gen(),
+            id,
             width,
             height,
+            material,
+            body_type: BodyType::Character(character),
+            shape: BodyShape::default()
+        };
+        let body_def = B2bodyDef {
+            position,
+            body_type: B2bodyType::B2DynamicBody,
+            gravity_scale: properties.gravity_scale() * character_type.gravity_scale(),
+            user_data: Some(body_data),
+            ..B2bodyDef::default()
+        };
+        let body = B2world::create_body(self.world.clone(), &body_def);
+
+        let fixture_def = B2fixtureDef {
+            shape: Some(shape),
+            density: properties.density(),
+            friction: properties.friction(),
+            restitution: properties.restitution(),
+            ..B2fixtureDef::default()
+        };
+        B2body::create_fixture(body.clone(), &fixture_def);
+
+        let body = self.body(body).unwrap();
+        events.push(GameEvent::Spawned(body));
+        events
+    }
+
+    /// typed letters are linked into word chains, random ones are left loose
+    pub fn spawn_asset(&mut self, sprite: SpriteAsset, typed: bool) -> Vec<GameEvent> {
+        let polygon_scale = self.config.polygon_scale;
+
+        let (sprite_width, sprite_height) = sprite.unit_scale();
+        let width = sprite_width as f32 * polygon_scale;
+        let height = sprite_height as f32 * polygon_scale;
+
+        let chain_tail = if typed { self.next_chain_link(width, height) } else { None };
+        let (position, to_destroy) = match &chain_tail {
+            Some((_, position)) => (*position, vec![]),
+            None => self.rng_spawn_position(width, height)
+        };
+        let mut events = self.destroy_bodies(to_destroy);
+
+        let properties = MaterialProperties::new(sprite.material(), &self.config);
+        let id = self.rng.gen();
+        self.last_spawned = Some(id);
+        let body_data = BodyData {
+            id,
+            width,
+            height,
+            material: sprite.material(),
             body_type: BodyType::Alphanumeric(
-                AlphanumericBody { name: sprite.name().to_string(), alphanumeric: sprite.character() }
-            )
+                AlphanumericBody { sprite: sprite.id(), alphanumeric: sprite.character() }
+            ),
+            shape: BodyShape::default()
         };
         let body_def = B2bodyDef {
             position,
             body_type: B2bodyType::B2DynamicBody,
+            gravity_scale: properties.gravity_scale(),
             user_data: Some(body_data),
             ..B2bodyDef::default()
         };
@@ -539,9 +1545,9 @@ impl Physics {
         let shape = Rc::new(RefCell::new(B2polygonShape::default()));
         let mut fixture_def = B2fixtureDef {
             shape: Some(shape.clone()),
-            density: self.config.body_density,
-            friction: self.config.body_friction,
-            restitution: self.config.body_restitution,
+            density: properties.density(),
+            friction: properties.friction(),
+            restitution: properties.restitution(),
             ..B2fixtureDef::default()
         };
 
@@ -558,12 +1564,185 @@ impl Physics {
             fixture_def.user_data = Some(FixtureData { id: self.rng.gen(), color: Color::RGB(r, g, b) });
             B2body::create_fixture(body.clone(), &fixture_def);
         }
+        self.attach_shape(&body);
+
+        if let Some(word_chain) = self.config.word_chain.filter(|_| typed) {
+            if let Some((tail, _)) = chain_tail {
+                self.link(tail, body.clone(), word_chain.joint);
+            }
+            self.chain_tail = Some(id);
+            self.time_since_last_keystroke = Duration::ZERO;
+        }
 
         let body = self.body(body).unwrap();
         events.push(GameEvent::Spawned(body));
         events
     }
 
+    /// the current word chain tail & the position to spawn the next letter alongside it,
+    /// none when the next letter should start a new word
+    fn next_chain_link(&self, width: f32, height: f32) -> Option<(BodyPtr<UserDataTypes>, B2vec2)> {
+        let word_chain = self.config.word_chain?;
+        if self.time_since_last_keystroke > Duration::from_millis(word_chain.max_keystroke_pause_ms) {
+            return None;
+        }
+
+        let tail = self.find_body(self.chain_tail?)?;
+        let (tail_position, tail_width) = {
+            let tail = tail.borrow();
+            (tail.get_position(), tail.get_user_data()?.width)
+        };
+
+        let position = tail_position + B2vec2::new((tail_width + width) / 2.0 + word_chain.link_length, 0.0);
+        let half_size = B2vec2::new(width / 2.0, height / 2.0);
+        let aabb = B2AABB { lower_bound: position - half_size, upper_bound: position + half_size };
+
+        // start a new word rather than spawn off screen or on top of another body
+        let (world_width, world_height) = self.scale.b2d_size();
+        let on_screen = aabb.lower_bound.x > 0.0 && aabb.lower_bound.y > 0.0
+            && aabb.upper_bound.x < world_width && aabb.upper_bound.y < world_height;
+        if !on_screen || !self.bodies_in_aabb(aabb).is_empty() {
+            return None;
+        }
+        Some((tail, position))
+    }
+
+    fn link(&mut self, tail: BodyPtr<UserDataTypes>, body: BodyPtr<UserDataTypes>, joint: ChainJoint) {
+        let (body_a, anchor_a) = {
+            let tail = tail.borrow();
+            (tail.get_user_data().unwrap().id, tail.get_position())
+        };
+        let (body_b, anchor_b) = {
+            let body = body.borrow();
+            (body.get_user_data().unwrap().id, body.get_position())
+        };
+
+        let id = self.rng.gen();
+        let def = match joint {
+            ChainJoint::Distance => {
+                let mut def = B2distanceJointDef::default();
+                def.initialize(tail, body, anchor_a, anchor_b);
+                // slack like a string, only taut at full length
+                def.min_length = 0.0;
+                def.base.user_data = Some(JointData { id });
+                B2JointDefEnum::DistanceJoint(def)
+            }
+            ChainJoint::Revolute => {
+                let mut anchor = anchor_a + anchor_b;
+                anchor *= 0.5;
+                let mut def = B2revoluteJointDef::default();
+                def.initialize(tail, body, anchor);
+                def.base.user_data = Some(JointData { id });
+                B2JointDefEnum::RevoluteJoint(def)
+            }
+        };
+
+        let joint = self.world.borrow_mut().create_joint(&def);
+        self.chain_links.push(ChainLink { id, body_a, body_b, joint });
+    }
+
+    /// breaks a letter into pieces of connected triangles, the pieces replace the letter
+    fn fracture(&mut self, body_ptr: BodyPtr<UserDataTypes>) -> Option<GameEvent> {
+        let config = self.config.fracture?;
+        let (data, angle, angular_velocity, transform, triangles) = {
+            let body = body_ptr.borrow();
+            let data = body.get_user_data()?;
+            if !matches!(data.body_type, BodyType::Alphanumeric(_)) {
+                return None;
+            }
+            let triangles = body.get_fixture_list().iter()
+                .filter_map(|fixture| {
+                    let fixture = fixture.borrow();
+                    let shape = fixture.get_shape();
+                    let polygon = shape.as_polygon()?;
+                    let vertices: [B2vec2; 3] = polygon.m_vertices[..polygon.m_count].try_into().ok()?;
+                    Some((vertices, fixture.get_user_data()?.color))
+                })
+                .collect::<Vec<([B2vec2; 3], Color)>>();
+            (data, body.get_angle(), body.get_angular_velocity(), body.get_transform(), triangles)
+        };
+
+        let vertices = triangles.iter().map(|(v, _)| *v).collect::<Vec<[B2vec2; 3]>>();
+        let groups = fracture::fracture(&vertices, config.pieces, &mut self.rng);
+        if groups.len() < 2 {
+            return None;
+        }
+
+        let pieces = groups.into_iter()
+            .map(|group| {
+                let mut centroid = B2vec2::zero();
+                for &index in group.iter() {
+                    for vertex in vertices[index].iter() {
+                        centroid += *vertex;
+                    }
+                }
+                centroid *= 1.0 / (group.len() * 3) as f32;
+                let position = b2_mul_transform_by_vec2(transform, centroid);
+                let velocity = body_ptr.borrow().get_linear_velocity_from_world_point(position);
+                (group, centroid, position, velocity)
+            })
+            .collect::<Vec<(Vec<usize>, B2vec2, B2vec2, B2vec2)>>();
+
+        let parent = self.body(body_ptr.clone())?;
+        // the pieces replace the parent, so it is not reported as destroyed
+        let _ = self.destroy_bodies(vec![body_ptr]);
+
+        let properties = MaterialProperties::new(data.material, &self.config);
+        let lifetime = Duration::from_millis(config.fragment_lifetime_ms);
+        let mut fragments = vec![];
+        for (group, centroid, position, velocity) in pieces {
+            // each piece is centered on its own centroid so it tumbles naturally
+            let local_triangles = group.iter()
+                .map(|&index| (vertices[index].map(|v| v - centroid), triangles[index].1))
+                .collect::<Vec<([B2vec2; 3], Color)>>();
+            let mut lower = B2vec2::zero();
+            let mut upper = B2vec2::zero();
+            for vertex in local_triangles.iter().flat_map(|(v, _)| v.iter()) {
+                lower = B2vec2::new(lower.x.min(vertex.x), lower.y.min(vertex.y));
+                upper = B2vec2::new(upper.x.max(vertex.x), upper.y.max(vertex.y));
+            }
+
+            let body_data = BodyData {
+                id: self.rng.gen(),
+                width: upper.x - lower.x,
+                height: upper.y - lower.y,
+                material: data.material,
+                body_type: BodyType::Fragment(FragmentBody { parent: data.id, age: Duration::ZERO, lifetime }),
+                shape: BodyShape::default()
+            };
+            let body_def = B2bodyDef {
+                position,
+                angle,
+                linear_velocity: velocity,
+                angular_velocity,
+                body_type: B2bodyType::B2DynamicBody,
+                gravity_scale: properties.gravity_scale(),
+                user_data: Some(body_data),
+                ..B2bodyDef::default()
+            };
+            let body = B2world::create_body(self.world.clone(), &body_def);
+
+            let shape = Rc::new(RefCell::new(B2polygonShape::default()));
+            let mut fixture_def = B2fixtureDef {
+                shape: Some(shape.clone()),
+                density: properties.density(),
+                friction: properties.friction(),
+                restitution: properties.restitution(),
+                ..B2fixtureDef::default()
+            };
+            for (points, color) in local_triangles {
+                shape.borrow_mut().set(&points);
+                fixture_def.user_data = Some(FixtureData { id: self.rng.gen(), color });
+                B2body::create_fixture(body.clone(), &fixture_def);
+            }
+            self.attach_shape(&body);
+
+            fragments.extend(self.body(body));
+        }
+
+        Some(GameEvent::Fractured { parent, pieces: fragments })
+    }
+
     pub fn destroy_body(&mut self, id: u128) -> Option<GameEvent> {
         self.destroy_bodies_by_id(HashSet::from([id])).first().cloned()
     }
@@ -575,44 +1754,323 @@ impl Physics {
             .collect()
     }
 
-    fn body(&self, body: Rc<RefCell<B2body<UserDataTypes>>>) -> Option<Body> {
-        let body = body.borrow();
-        if let Some(data) = body.get_user_data() {
-            let position = body.get_position();
-            let aabb = Rect::from_center(
-                self.scale.b2d_vec2_to_sdl(position),
-                self.scale.b2d_to_sdl(data.width) as u32,
-                self.scale.b2d_to_sdl(data.height) as u32
-            );
-            let angle = body.get_angle() as f64 * 180.0 / PI;
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let bodies = self.world.borrow().get_body_list().iter()
+            .filter_map(|body| {
+                let body = body.borrow();
+                let data = body.get_user_data()?;
+                let triangles = if matches!(data.body_type, BodyType::Character(_) | BodyType::Segment(_)) {
+                    vec![]
+                } else {
+                    body.get_fixture_list().iter()
+                        .filter_map(|fixture| {
+                            let fixture = fixture.borrow();
+                            let shape = fixture.get_shape();
+                            let polygon = shape.as_polygon()?;
+                            let vertices: [B2vec2; 3] = polygon.m_vertices[..polygon.m_count].try_into().ok()?;
+                            let color = fixture.get_user_data()?.color;
+                            Some((vertices.map(|v| (v.x, v.y)), (color.r, color.g, color.b)))
+                        })
+                        .collect()
+                };
+                let position = body.get_position();
+                let linear_velocity = body.get_linear_velocity();
+                Some(BodySnapshot {
+                    id: data.id,
+                    body_type: data.body_type,
+                    material: data.material,
+                    width: data.width,
+                    height: data.height,
+                    motion: body.get_type().into(),
+                    enabled: body.is_enabled(),
+                    position: (position.x, position.y),
+                    angle: body.get_angle(),
+                    linear_velocity: (linear_velocity.x, linear_velocity.y),
+                    angular_velocity: body.get_angular_velocity(),
+                    triangles,
+                    sprite: None
+                })
+            })
+            .collect();
+
+        WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            level: self.level,
+            time_in_level: self.time_in_level,
+            bodies,
+            links: self.chain_links.iter().map(|link| (link.body_a, link.body_b)).collect()
+        }
+    }
 
-            let transform = body.get_transform();
-            let mut polygons = vec![];
-            for fixture in body.get_fixture_list().iter() {
-                let fixture = fixture.borrow();
-                if let Some(polygon) = fixture.get_shape().as_polygon() {
-                    let points: [Point; 3] = polygon.m_vertices[..polygon.m_count].iter()
-                        .map(|v| self.scale.b2d_vec2_to_sdl(b2_mul_transform_by_vec2(transform, *v)))
-                        .collect::<Vec<Point>>()
-                        .try_into()
-                        .unwrap();
-                    let color = fixture.get_user_data().unwrap().color;
-                    polygons.push(Triangle::new(points, color));
+    /// replaces everything in the world with the snapshot
+    pub fn restore(&mut self, snapshot: WorldSnapshot) {
+        {
+            let mut world = self.world.borrow_mut();
+            for link in self.chain_links.drain(..) {
+                world.destroy_joint(link.joint);
+            }
+            // carried letters are dropped, characters pick up something else
+            for carried in self.carried.drain(..) {
+                world.destroy_joint(carried.joint);
+            }
+            let bodies = world.get_body_list().iter()
+                .filter(|body| body.borrow().get_user_data().is_some())
+                .collect::<Vec<BodyPtr<UserDataTypes>>>();
+            for body in bodies {
+                world.destroy_body(body);
+            }
+        }
+        self.chain_reaction.clear();
+        self.pending_fractures.clear();
+        self.pending_stuns.clear();
+        self.chain_tail = None;
+        self.last_spawned = None;
+        self.level = snapshot.level;
+        self.layout = snapshot.level.map(Self::layout).unwrap_or_default();
+        self.time_in_level = snapshot.time_in_level;
+
+        let (world_width, world_height) = self.scale.b2d_size();
+        let mut to_disable = vec![];
+        for body in snapshot.bodies {
+            // the window may have shrunk since the snapshot was taken
+            let (x, y) = body.position;
+            if x < 0.0 || y < 0.0 || x > world_width || y > world_height {
+                continue;
+            }
+            let enabled = body.enabled;
+            let body = self.restore_body(body);
+            if !enabled {
+                to_disable.push(body);
+            }
+        }
+        for body_ptr in to_disable {
+            B2body::set_enabled(body_ptr, false);
+        }
+
+        let owners = self.world.borrow().get_body_list().iter()
+            .filter_map(|body| match body.borrow().get_user_data()?.body_type {
+                BodyType::Character(_) => Some(body.clone()),
+                _ => None
+            })
+            .collect::<Vec<BodyPtr<UserDataTypes>>>();
+        for owner in owners {
+            let id = owner.borrow().get_user_data().unwrap().id;
+            let mut front = owner;
+            for (segment, _) in self.segments(id) {
+                self.join_segment(front, segment.clone());
+                front = segment;
+            }
+        }
+
+        if let Some(word_chain) = self.config.word_chain {
+            for (body_a, body_b) in snapshot.links {
+                if let (Some(tail), Some(body)) = (self.find_body(body_a), self.find_body(body_b)) {
+                    self.link(tail, body, word_chain.joint);
                 }
             }
+        }
+    }
 
-            Some(Body {
-                id: data.id,
-                aabb,
-                angle,
-                polygons,
-                body_type: data.body_type
+    fn restore_body(&mut self, snapshot: BodySnapshot) -> BodyPtr<UserDataTypes> {
+        let properties = MaterialProperties::new(snapshot.material, &self.config);
+        let character_type = match &snapshot.body_type {
+            BodyType::Character(character) => Some(character.character_type()),
+            BodyType::Segment(segment) => Some(segment.character_type),
+            _ => None
+        };
+        let shape = match &snapshot.body_type {
+            BodyType::Segment(segment) => self.disc(snapshot.width / 2.0, segment.color),
+            _ => BodyShape::default()
+        };
+        let is_pivot = matches!(snapshot.body_type, BodyType::Prop) && snapshot.motion == BodyMotion::Dynamic;
+        let position = B2vec2::new(snapshot.position.0, snapshot.position.1);
+
+        let body_def = B2bodyDef {
+            position,
+            angle: snapshot.angle,
+            linear_velocity: B2vec2::new(snapshot.linear_velocity.0, snapshot.linear_velocity.1),
+            angular_velocity: snapshot.angular_velocity,
+            body_type: snapshot.motion.into(),
+            gravity_scale: properties.gravity_scale() * character_type.map_or(1.0, |c| c.gravity_scale()),
+            user_data: Some(BodyData {
+                id: snapshot.id,
+                width: snapshot.width,
+                height: snapshot.height,
+                material: snapshot.material,
+                body_type: snapshot.body_type,
+                shape
+            }),
+            ..B2bodyDef::default()
+        };
+        let body = B2world::create_body(self.world.clone(), &body_def);
+
+        match character_type {
+            Some(character_type) => {
+                let fixture_def = B2fixtureDef {
+                    shape: Some(self.character_factory.shape(character_type)),
+                    density: properties.density(),
+                    friction: properties.friction(),
+                    restitution: properties.restitution(),
+                    ..B2fixtureDef::default()
+                };
+                B2body::create_fixture(body.clone(), &fixture_def);
+            }
+            None => {
+                let shape = Rc::new(RefCell::new(B2polygonShape::default()));
+                let mut fixture_def = B2fixtureDef {
+                    shape: Some(shape.clone()),
+                    density: properties.density(),
+                    friction: properties.friction(),
+                    restitution: properties.restitution(),
+                    ..B2fixtureDef::default()
+                };
+                for (points, (r, g, b)) in snapshot.triangles {
+                    shape.borrow_mut().set(&points.map(|(x, y)| B2vec2::new(x, y)));
+                    fixture_def.user_data = Some(FixtureData { id: self.rng.gen(), color: Color::RGB(r, g, b) });
+                    B2body::create_fixture(body.clone(), &fixture_def);
+                }
+                self.attach_shape(&body);
+            }
+        }
+
+        if is_pivot {
+            let mut def = B2revoluteJointDef::default();
+            def.initialize(self.ground.clone(), body.clone(), position);
+            def.base.user_data = Some(JointData { id: self.rng.gen() });
+            self.world.borrow_mut().create_joint(&B2JointDefEnum::RevoluteJoint(def));
+        }
+        body
+    }
+
+    /// the body under a point on screen
+    pub fn body_at(&self, point: Point) -> Option<Body> {
+        let point = self.scale.point_to_b2d_vec2(point);
+        let aabb = B2AABB {
+            lower_bound: B2vec2::new(point.x - 0.001, point.y - 0.001),
+            upper_bound: B2vec2::new(point.x + 0.001, point.y + 0.001)
+        };
+        let mut id = None;
+        self.world.borrow().query_aabb(|f: FixturePtr<UserDataTypes>| {
+            let fixture = f.borrow();
+            if fixture.test_point(point) {
+                id = fixture.get_body().borrow().get_user_data().map(|d| d.id);
+            }
+            // keep looking until a body is found
+            id.is_none()
+        }, aabb);
+        id.and_then(|id| self.find_body(id)).and_then(|b| self.body(b))
+    }
+
+    /// bodies overlapping a rectangle on screen
+    pub fn bodies_in_rect(&self, rect: Rect) -> Vec<Body> {
+        let top_left = self.scale.point_to_b2d_vec2(rect.top_left());
+        let bottom_right = self.scale.point_to_b2d_vec2(rect.bottom_right());
+        let aabb = B2AABB {
+            lower_bound: B2vec2::new(top_left.x.min(bottom_right.x), top_left.y.min(bottom_right.y)),
+            upper_bound: B2vec2::new(top_left.x.max(bottom_right.x), top_left.y.max(bottom_right.y))
+        };
+        self.bodies_in_aabb(aabb).into_iter()
+            .filter_map(|b| self.body(b))
+            .collect()
+    }
+
+    /// the first body hit by a ray between two points on screen, the world edges are ignored
+    pub fn ray_cast(&self, from: Point, to: Point) -> Option<RayHit> {
+        let mut closest: Option<(u128, B2vec2, B2vec2, f32)> = None;
+        self.world.borrow().ray_cast(|f: FixturePtr<UserDataTypes>, point: B2vec2, normal: B2vec2, fraction: f32| {
+            match f.borrow().get_body().borrow().get_user_data() {
+                Some(data) => {
+                    closest = Some((data.id, point, normal, fraction));
+                    // clip the ray so only closer hits are reported
+                    fraction
+                }
+                None => -1.0
+            }
+        }, self.scale.point_to_b2d_vec2(from), self.scale.point_to_b2d_vec2(to));
+
+        let (id, point, normal, fraction) = closest?;
+        let body = self.body(self.find_body(id)?)?;
+        Some(RayHit {
+            body,
+            point: self.scale.b2d_vec2_to_sdl(point),
+            normal: (normal.x, normal.y),
+            fraction
+        })
+    }
+
+    /// the body closest to a point on screen that matches the filter
+    pub fn nearest(&self, point: Point, filter: BodyFilter) -> Option<Body> {
+        let point = self.scale.point_to_b2d_vec2(point);
+        let nearest = self.world.borrow().get_body_list().iter()
+            .filter(|body| matches!(body.borrow().get_user_data(), Some(data) if filter.matches(&data.body_type)))
+            .min_by(|a, b| {
+                let distance_a = (a.borrow().get_position() - point).length_squared();
+                let distance_b = (b.borrow().get_position() - point).length_squared();
+                distance_a.total_cmp(&distance_b)
+            });
+        nearest.and_then(|body| self.body(body))
+    }
+
+    /// stores the polygon fixtures of a body in its user data so snapshots of the body can share them
+    fn attach_shape(&self, body: &BodyPtr<UserDataTypes>) {
+        let mut body = body.borrow_mut();
+        let shape = body.get_fixture_list().iter()
+            .filter_map(|fixture| {
+                let fixture = fixture.borrow();
+                let shape = fixture.get_shape();
+                let polygon = shape.as_polygon()?;
+                let vertices: [B2vec2; 3] = polygon.m_vertices[..polygon.m_count].try_into().ok()?;
+                Some(LocalTriangle {
+                    points: vertices.map(|v| self.scale.b2d_vec2_to_sdl_f32(v)),
+                    color: fixture.get_user_data()?.color
+                })
             })
-        } else {
-            None
+            .collect::<Vec<LocalTriangle>>();
+        if let Some(mut data) = body.get_user_data() {
+            data.shape = Arc::new(shape);
+            body.set_user_data(&data);
         }
     }
 
+    /// clears the buffer & fills it with every body, reusing its allocation
+    pub fn bodies_into(&self, bodies: &mut Vec<Body>) {
+        bodies.clear();
+        bodies.extend(self.world.borrow().get_body_list().iter().filter_map(|b| self.body(b)));
+    }
+
+    fn body(&self, body: Rc<RefCell<B2body<UserDataTypes>>>) -> Option<Body> {
+        let body = body.borrow();
+        let data = body.get_user_data()?;
+        let position = body.get_position();
+        let aabb = Rect::from_center(
+            self.scale.b2d_vec2_to_sdl(position),
+            self.scale.b2d_to_sdl(data.width) as u32,
+            self.scale.b2d_to_sdl(data.height) as u32
+        );
+        let angle = body.get_angle() as f64 * 180.0 / PI;
+
+        let joints = self.chain_links.iter()
+            .filter(|link| link.body_a == data.id)
+            .map(|link| {
+                let joint = link.joint.borrow();
+                BodyJoint {
+                    from: self.scale.b2d_vec2_to_sdl(joint.get_anchor_a()),
+                    to: self.scale.b2d_vec2_to_sdl(joint.get_anchor_b())
+                }
+            })
+            .collect();
+
+        Some(Body {
+            id: data.id,
+            aabb,
+            angle,
+            center: self.scale.b2d_vec2_to_sdl_f32(position),
+            shape: data.shape,
+            joints,
+            body_type: data.body_type
+        })
+    }
+
     fn destroy_bodies_by_id(&mut self, mut ids: HashSet<u128>) -> Vec<GameEvent> {
         if ids.is_empty() {
             return vec![]
@@ -659,7 +2117,8 @@ impl Physics {
                     if let Some(body_data) = body.get_user_data().as_mut() {
                         if let BodyType::Character(character) = &mut body_data.body_type {
                             behaviour = match character.state() {
-                                CharacterState::Alive => {
+                                CharacterState::Spawning | CharacterState::Alive | CharacterState::Idle
+                                | CharacterState::Sleeping | CharacterState::Attacking | CharacterState::Stunned => {
                                     // "kill" this character
                                     character.destroy();
                                     body.set_user_data(body_data);
@@ -686,6 +2145,27 @@ impl Physics {
                             if notify {
                                 result.push(event);
                             }
+
+                            // joints cannot outlive either of their bodies
+                            let id = body_ptr.borrow().get_user_data().map(|d| d.id);
+                            if let Some(id) = id {
+                                self.chain_links.retain(|link| {
+                                    if link.body_a == id || link.body_b == id {
+                                        world.destroy_joint(link.joint.clone());
+                                        false
+                                    } else {
+                                        true
+                                    }
+                                });
+                                self.carried.retain(|carried| {
+                                    if carried.character == id || carried.body == id {
+                                        world.destroy_joint(carried.joint.clone());
+                                        false
+                                    } else {
+                                        true
+                                    }
+                                });
+                            }
                             world.destroy_body(body_ptr)
                         },
                         Behaviour::Disable => {
@@ -704,3 +2184,335 @@ impl Physics {
         result
     }
 }
+
+fn is_prop(body: &BodyPtr<UserDataTypes>) -> bool {
+    matches!(body.borrow().get_user_data(), Some(data) if matches!(data.body_type, BodyType::Prop))
+}
+
+fn character(body: &BodyPtr<UserDataTypes>) -> Option<(u128, Character)> {
+    match body.borrow().get_user_data()? {
+        BodyData { id, body_type: BodyType::Character(character), .. } => Some((id, character)),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+    use crate::assets::geometry::{SpritePoint, SpriteSnip, SpriteTriangle};
+    use crate::config::{Config, LevelConfig};
+    use super::*;
+
+    fn physics_with_bodies(count: usize) -> Physics {
+        let mut config = Config::default().physics;
+        config.polygon_scale = 1.0;
+        config.word_chain = None;
+        config.level = LevelConfig::Empty;
+        let mut physics = Physics::new(PhysicsScale::new(1920, 1080, config), config);
+        for _ in 0..count {
+            physics.spawn_asset(letter(), false);
+        }
+        physics
+    }
+
+    fn props(physics: &Physics) -> Vec<BodyPtr<UserDataTypes>> {
+        physics.world.borrow().get_body_list().iter().filter(is_prop).collect()
+    }
+
+    fn overlaps_prop(physics: &Physics, id: u128) -> bool {
+        let body = physics.find_body(id).unwrap();
+        let data = body.borrow().get_user_data().unwrap();
+        let position = body.borrow().get_position();
+        let aabb = B2AABB {
+            lower_bound: B2vec2::new(position.x - data.width / 2.0, position.y - data.height / 2.0),
+            upper_bound: B2vec2::new(position.x + data.width / 2.0, position.y + data.height / 2.0)
+        };
+        physics.bodies_in_aabb(aabb).iter().any(is_prop)
+    }
+
+    fn letter() -> SpriteAsset {
+        let triangle = SpriteTriangle::new(
+            [SpritePoint::new(0.0, 0.0), SpritePoint::new(1.0, 0.0), SpritePoint::new(0.0, 1.0)],
+            [255, 255, 255]
+        );
+        SpriteAsset::new("a".to_string(), 'a', SpriteSnip::new(0, 0, 10, 10), vec![triangle], Material::Default)
+    }
+
+    fn is_alive(physics: &Physics, id: u128) -> bool {
+        match physics.find_body(id).and_then(|body| body.borrow().get_user_data()).map(|data| data.body_type) {
+            Some(BodyType::Character(character)) => character.lifetime().state().is_alive(),
+            _ => false
+        }
+    }
+
+    #[test]
+    fn snake_turning_from_a_wall_survives_brushing_its_neck() {
+        let mut physics = physics_with_bodies(0);
+        physics.spawn_character(CharacterType::SNAKE);
+        let snake = physics.last_spawned.unwrap();
+        for _ in 0..3 {
+            physics.grow_segment(snake, CharacterType::SNAKE, (255, 255, 255));
+        }
+        let segments = physics.segments(snake).into_iter()
+            .map(|(body, _)| body.borrow().get_user_data().unwrap().id)
+            .collect::<Vec<u128>>();
+        let segment = |index: usize| ContactedBody {
+            body_id: segments[index],
+            material: Material::Default,
+            kind: BodyKind::Segment { owner: snake }
+        };
+        let head = ContactedBody { body_id: snake, material: Material::Default, kind: BodyKind::Character(CharacterType::SNAKE) };
+
+        // hits the wall & folds back onto the segments behind its head as it turns
+        let neck = [segment(0), segment(1)];
+        {
+            let mut listener = physics.contact_listener.borrow_mut();
+            listener.contacts.insert(Contact::with_ground(head));
+            for neck in neck {
+                listener.contacts.insert(Contact::between_bodies(head, neck));
+            }
+        }
+        physics.update(Duration::from_millis(16));
+        assert!(is_alive(&physics, snake));
+
+        let tail = segment(2);
+        physics.contact_listener.borrow_mut().contacts.insert(Contact::between_bodies(head, tail));
+        physics.update(Duration::from_millis(16));
+        assert!(!is_alive(&physics, snake));
+    }
+
+    #[test]
+    fn a_level_that_cannot_load_leaves_an_empty_world() {
+        let mut physics = physics_with_bodies(0);
+        physics.load_level(LevelName::Workshop);
+        assert!(!props(&physics).is_empty());
+        physics.load_level(LevelName::Custom(u16::MAX));
+        assert!(props(&physics).is_empty());
+    }
+
+    /// plays the character's spawn animation so it can eat & be eaten, after eating the letters
+    fn ready(physics: &mut Physics, id: u128, letters: Vec<char>) {
+        let body = physics.find_body(id).unwrap();
+        let mut data = body.borrow().get_user_data().unwrap();
+        if let BodyType::Character(character) = &mut data.body_type {
+            let world_state = CharacterWorldState::new(false, None, vec![]).with_letters(letters);
+            physics.character_factory.update(character, Duration::from_secs(1), world_state);
+        }
+        body.borrow_mut().set_user_data(&data);
+    }
+
+    /// pac-man runs into blinky after eating the letters, who eats who
+    fn pac_man_meets_blinky(letters: Vec<char>) -> Option<(CharacterType, CharacterType)> {
+        let mut physics = physics_with_bodies(0);
+        let mut spawn = |character, letters, x| {
+            physics.spawn_character(character);
+            let id = physics.last_spawned.unwrap();
+            ready(&mut physics, id, letters);
+            physics.find_body(id).unwrap().borrow_mut().set_transform(B2vec2::new(x, 50.0), 0.0);
+        };
+        spawn(CharacterType::PAC_MAN, letters, 50.0);
+        spawn(CharacterType::BLINKY, vec![], 50.5);
+        (0..10).flat_map(|_| physics.update(Duration::from_millis(16)))
+            .find_map(|event| match event {
+                GameEvent::CharacterEats { predator, prey } => Some((predator, prey)),
+                _ => None
+            })
+    }
+
+    #[test]
+    fn powered_up_pac_man_eats_ghosts_otherwise_they_eat_him() {
+        assert_eq!(pac_man_meets_blinky(vec!['O']), Some((CharacterType::PAC_MAN, CharacterType::BLINKY)));
+        assert_eq!(pac_man_meets_blinky(vec![]), Some((CharacterType::BLINKY, CharacterType::PAC_MAN)));
+    }
+
+    #[test]
+    fn only_typed_letters_are_chained() {
+        let mut physics = physics_with_bodies(0);
+        physics.config.word_chain = Config::default().physics.word_chain;
+        physics.spawn_asset(letter(), true);
+        // away from the edges so the next letter has room alongside it
+        physics.find_body(physics.last_spawned.unwrap()).unwrap()
+            .borrow_mut().set_transform(B2vec2::new(96.0, 54.0), 0.0);
+        physics.spawn_asset(letter(), true);
+        assert_eq!(physics.chain_links.len(), 1);
+        physics.spawn_asset(letter(), false);
+        assert_eq!(physics.chain_links.len(), 1);
+    }
+
+    #[test]
+    fn default_explosions_chain_two_deep() {
+        let mut physics = physics_with_bodies(2);
+        let bodies = physics.world.borrow().get_body_list().iter()
+            .filter(|body| body.borrow().get_type() == B2bodyType::B2DynamicBody)
+            .collect::<Vec<BodyPtr<UserDataTypes>>>();
+        // the first is caught by the explosion, the second only by the first going off
+        for (body, x) in bodies.iter().zip([80.0, 120.0]) {
+            body.borrow_mut().set_transform(B2vec2::new(x, 50.0), 0.0);
+        }
+        let step = |physics: &mut Physics| physics.world.borrow_mut()
+            .step(1.0 / 60.0, physics.config.velocity_iterations, physics.config.position_iterations);
+        let pushed = |body: &BodyPtr<UserDataTypes>| body.borrow().get_linear_velocity().x > 0.0;
+
+        physics.explode(B2vec2::new(20.0, 50.0), 1.0, 0);
+        step(&mut physics);
+        assert!(pushed(&bodies[0]));
+        assert!(!pushed(&bodies[1]));
+
+        let mut strengths = vec![];
+        for _ in 0..10 {
+            let events = physics.update_pending_explosions(Duration::from_millis(100));
+            for event in events {
+                if let GameEvent::Explosion { strength, .. } = event {
+                    strengths.push(strength);
+                    step(&mut physics);
+                    if strengths.len() == 1 {
+                        assert!(pushed(&bodies[1]));
+                    }
+                }
+            }
+        }
+        // one secondary explosion per generation, each weaker than the last
+        assert_eq!(strengths, vec![0.75, 0.75 * 0.75]);
+    }
+
+    #[test]
+    fn bodies_spawn_clear_of_props() {
+        let mut physics = physics_with_bodies(0);
+        physics.load_level(LevelName::Workshop);
+        for index in 0..20 {
+            if index % 2 == 0 {
+                physics.spawn_character(CharacterType::PAC_MAN);
+            } else {
+                physics.spawn_asset(letter(), false);
+            }
+            assert!(!overlaps_prop(&physics, physics.last_spawned.unwrap()));
+        }
+    }
+
+    #[test]
+    fn resizing_moves_bodies_off_the_rebuilt_props() {
+        let mut physics = physics_with_bodies(1);
+        physics.load_level(LevelName::Workshop);
+        let prop_count = props(&physics).len();
+        let letter = physics.last_spawned.unwrap();
+        let body = physics.find_body(letter).unwrap();
+        let on_a_prop = props(&physics).into_iter()
+            .map(|prop| prop.borrow().get_position())
+            .find(|&position| {
+                body.borrow_mut().set_transform(position, 0.0);
+                overlaps_prop(&physics, letter)
+            })
+            .expect("a prop to put the letter on");
+        physics.resize(PhysicsScale::new(1280, 720, physics.config));
+        assert_eq!(props(&physics).len(), prop_count);
+        assert!(!overlaps_prop(&physics, letter));
+        assert_ne!(physics.find_body(letter).unwrap().borrow().get_position(), on_a_prop);
+    }
+
+    /// run with `cargo test --release bodies_benchmark -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bodies_benchmark() {
+        const FRAMES: u32 = 1000;
+        for count in [50, 200, 1000] {
+            let physics = physics_with_bodies(count);
+            let mut bodies = vec![];
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                physics.bodies_into(&mut bodies);
+            }
+            let per_frame = start.elapsed() / FRAMES;
+            println!("{} bodies: {:?} per frame", bodies.len(), per_frame);
+        }
+    }
+
+    #[test]
+    fn bodies_into_reuses_the_buffer() {
+        for count in [50, 200] {
+            let physics = physics_with_bodies(count);
+            let mut bodies = vec![];
+            physics.bodies_into(&mut bodies);
+            physics.bodies_into(&mut bodies);
+            assert_eq!(bodies.len(), count);
+        }
+    }
+
+    /// letters moved to the positions in meters, their ids in the same order
+    fn letters_at(positions: &[(f32, f32)]) -> (Physics, Vec<u128>) {
+        let physics = physics_with_bodies(positions.len());
+        let bodies = physics.world.borrow().get_body_list().iter()
+            .filter(|body| body.borrow().get_type() == B2bodyType::B2DynamicBody)
+            .collect::<Vec<BodyPtr<UserDataTypes>>>();
+        let mut ids = vec![];
+        for (body, &(x, y)) in bodies.iter().zip(positions) {
+            body.borrow_mut().set_transform(B2vec2::new(x, y), 0.0);
+            ids.push(body.borrow().get_user_data().unwrap().id);
+        }
+        (physics, ids)
+    }
+
+    #[test]
+    fn collision_events_are_limited_to_a_burst_then_the_sustained_rate() {
+        // pairs of letters smashing into each other
+        let positions = (0..10)
+            .flat_map(|pair| [(10.0 + pair as f32 * 10.0, 50.0), (10.5 + pair as f32 * 10.0, 50.0)])
+            .collect::<Vec<(f32, f32)>>();
+        let (mut physics, ids) = letters_at(&positions);
+        for (index, &id) in ids.iter().enumerate() {
+            let speed = if index % 2 == 0 { 100.0 } else { -100.0 };
+            physics.find_body(id).unwrap().borrow_mut().set_linear_velocity(B2vec2::new(speed, 0.0));
+        }
+        let limit = physics.config.collision_events;
+        let collisions = |events: Vec<GameEvent>| events.iter().filter(|event| matches!(event, GameEvent::Collision(_))).count();
+
+        assert_eq!(collisions(physics.update(Duration::from_millis(16))), limit.burst as usize);
+        let sustained = (0..10).map(|_| collisions(physics.update(Duration::from_millis(100)))).sum::<usize>();
+        assert!(sustained as f32 <= limit.max_per_second);
+    }
+
+    #[test]
+    fn world_settles_once_when_still_long_enough_and_wakes_when_something_moves() {
+        let mut physics = physics_with_bodies(0);
+        let settle_ms = physics.config.settle.unwrap().settle_ms;
+        let run = |physics: &mut Physics, ms: u64| (0..ms / 100)
+            .flat_map(|_| physics.update(Duration::from_millis(100)))
+            .filter_map(|event| match event {
+                GameEvent::WorldSettled => Some("settled"),
+                GameEvent::WorldWoke => Some("woke"),
+                _ => None
+            })
+            .collect::<Vec<&'static str>>();
+
+        assert!(run(&mut physics, settle_ms - 100).is_empty());
+        assert_eq!(run(&mut physics, 200), vec!["settled"]);
+        assert!(run(&mut physics, 1000).is_empty());
+
+        physics.spawn_asset(letter(), false);
+        physics.find_body(physics.last_spawned.unwrap()).unwrap()
+            .borrow_mut().set_linear_velocity(B2vec2::new(10.0, 0.0));
+        assert_eq!(run(&mut physics, 100), vec!["woke"]);
+    }
+
+    #[test]
+    fn queries_find_the_letters_on_screen() {
+        let (physics, ids) = letters_at(&[(50.0, 50.0), (80.0, 50.0)]);
+        let id = |body: Option<Body>| body.map(|body| body.id);
+
+        // ten pixels to the meter, the letters' triangle lies up & left of their center
+        assert_eq!(id(physics.body_at(Point::new(497, 497))), Some(ids[0]));
+        assert_eq!(id(physics.body_at(Point::new(650, 497))), None);
+
+        let in_rect = physics.bodies_in_rect(Rect::new(400, 400, 200, 200));
+        assert_eq!(in_rect.into_iter().map(|body| body.id).collect::<Vec<u128>>(), vec![ids[0]]);
+
+        // coming from the right the second letter is in the way of the first
+        let hit = physics.ray_cast(Point::new(1000, 497), Point::new(0, 497)).unwrap();
+        assert_eq!(hit.body.id, ids[1]);
+        assert!(hit.point.x() > 790 && hit.point.x() < 810);
+        assert!(physics.ray_cast(Point::new(1000, 300), Point::new(0, 300)).is_none());
+
+        assert_eq!(id(physics.nearest(Point::new(600, 500), BodyFilter::Alphanumeric)), Some(ids[0]));
+        assert_eq!(id(physics.nearest(Point::new(700, 500), BodyFilter::Any)), Some(ids[1]));
+        assert_eq!(id(physics.nearest(Point::new(600, 500), BodyFilter::Character)), None);
+    }
+}
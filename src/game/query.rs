@@ -0,0 +1,35 @@
+use sdl2::rect::Point;
+use crate::game::physics::{Body, BodyType};
+
+/// Which bodies a spatial query considers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFilter {
+    Any,
+    Alphanumeric,
+    Character,
+    Fragment,
+    Prop
+}
+
+impl BodyFilter {
+    pub fn matches(&self, body_type: &BodyType) -> bool {
+        match self {
+            BodyFilter::Any => true,
+            BodyFilter::Alphanumeric => matches!(body_type, BodyType::Alphanumeric(_)),
+            BodyFilter::Character => matches!(body_type, BodyType::Character(_)),
+            BodyFilter::Fragment => matches!(body_type, BodyType::Fragment(_)),
+            BodyFilter::Prop => matches!(body_type, BodyType::Prop)
+        }
+    }
+}
+
+/// The first body hit by a ray, in screen coordinates
+#[derive(Debug, Clone)]
+pub struct RayHit {
+    pub body: Body,
+    pub point: Point,
+    /// unit surface normal at the hit point
+    pub normal: (f32, f32),
+    /// how far along the ray the hit is, from 0 at the start to 1 at the end
+    pub fraction: f32
+}
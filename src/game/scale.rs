@@ -8,21 +8,47 @@ use crate::game::polygon::Triangle;
 #[derive(Debug, Clone)]
 pub struct PhysicsScale {
     config: PhysicsConfig,
+    /// drawable size in physical pixels
     width: u32,
-    height: u32
+    height: u32,
+    /// physical pixels per logical window point, above 1 on HiDPI displays
+    pixel_ratio: f32
 }
 
 impl PhysicsScale {
     pub fn new(width: u32, height: u32, config: PhysicsConfig) -> Self {
-        Self { width, height, config }
+        Self { width, height, config, pixel_ratio: 1.0 }
+    }
+
+    pub fn with_pixel_ratio(self, pixel_ratio: f32) -> Self {
+        Self { pixel_ratio, ..self }
+    }
+
+    /// the same scale for a new drawable size
+    pub fn resized(&self, width: u32, height: u32, pixel_ratio: f32) -> Self {
+        Self { width, height, config: self.config, pixel_ratio }
+    }
+
+    /// pixels_per_meter is configured in logical points so the world is the same size on any display
+    fn pixels_per_meter(&self) -> f32 {
+        self.config.pixels_per_meter * self.pixel_ratio
+    }
+
+    /// converts a point from window events, which are in logical points, to physical pixels
+    pub fn window_point_to_sdl(&self, x: i32, y: i32) -> Point {
+        Point::new(
+            (x as f32 * self.pixel_ratio).round() as i32,
+            (y as f32 * self.pixel_ratio).round() as i32
+        )
     }
 
     pub fn b2d_to_sdl(&self, value: f32) -> i32 {
-        (value * self.config.pixels_per_meter).round() as i32
+        (value * self.pixels_per_meter()).round() as i32
     }
 
-    pub fn point_to_b2d_vec2(&self, point: SpritePoint) -> B2vec2 {
-        B2vec2::new(point.x as f32 / self.config.pixels_per_meter, point.y as f32 / self.config.pixels_per_meter)
+    pub fn point_to_b2d_vec2<P: Into<SpritePoint>>(&self, point: P) -> B2vec2 {
+        let point = point.into();
+        B2vec2::new(point.x as f32 / self.pixels_per_meter(), point.y as f32 / self.pixels_per_meter())
     }
 
     pub fn b2d_vec2_to_sdl(&self, value: B2vec2) -> Point {
@@ -32,8 +58,13 @@ impl PhysicsScale {
         )
     }
 
+    /// unrounded pixels, for geometry that is transformed again before it is drawn
+    pub fn b2d_vec2_to_sdl_f32(&self, value: B2vec2) -> (f32, f32) {
+        (value.x * self.pixels_per_meter(), value.y * self.pixels_per_meter())
+    }
+
     pub fn b2d_size(&self) -> (f32, f32) {
-        (self.width as f32 / self.config.pixels_per_meter, self.height as f32 / self.config.pixels_per_meter)
+        (self.width as f32 / self.pixels_per_meter(), self.height as f32 / self.pixels_per_meter())
     }
 
     pub fn triangle_to_polygon(&self, triangle: &SpriteTriangle) -> Triangle {
@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use box2d_rs::b2_body::B2bodyType;
+use serde::{Deserialize, Serialize};
+use crate::assets::geometry::Material;
+use crate::assets::levels::LevelName;
+use crate::game::physics::BodyType;
+
+/// bumped whenever a change to the snapshot would break resuming an older session
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything needed to recreate the physics world, e.g. to resume a session after a restart
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// sessions saved before snapshots were versioned are 0
+    #[serde(default)]
+    pub version: u32,
+    pub level: Option<LevelName>,
+    pub time_in_level: Duration,
+    pub bodies: Vec<BodySnapshot>,
+    /// word chain links between the ids of neighbouring letters
+    pub links: Vec<(u128, u128)>
+}
+
+impl WorldSnapshot {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let snapshot: Self = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(format!("snapshot version {} is not {}", snapshot.version, SNAPSHOT_VERSION));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BodyMotion {
+    Static,
+    Kinematic,
+    Dynamic
+}
+
+impl From<B2bodyType> for BodyMotion {
+    fn from(value: B2bodyType) -> Self {
+        match value {
+            B2bodyType::B2StaticBody => BodyMotion::Static,
+            B2bodyType::B2KinematicBody => BodyMotion::Kinematic,
+            B2bodyType::B2DynamicBody => BodyMotion::Dynamic
+        }
+    }
+}
+
+impl From<BodyMotion> for B2bodyType {
+    fn from(value: BodyMotion) -> Self {
+        match value {
+            BodyMotion::Static => B2bodyType::B2StaticBody,
+            BodyMotion::Kinematic => B2bodyType::B2KinematicBody,
+            BodyMotion::Dynamic => B2bodyType::B2DynamicBody
+        }
+    }
+}
+
+/// A body in world coordinates, including the state of any character it carries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodySnapshot {
+    pub id: u128,
+    pub body_type: BodyType,
+    pub material: Material,
+    pub width: f32,
+    pub height: f32,
+    pub motion: BodyMotion,
+    pub enabled: bool,
+    pub position: (f32, f32),
+    /// radians
+    pub angle: f32,
+    pub linear_velocity: (f32, f32),
+    pub angular_velocity: f32,
+    /// triangles relative to the body & their colors, characters are rebuilt from their type instead
+    pub triangles: Vec<([(f32, f32); 3], (u8, u8, u8))>,
+    /// name of an alphanumeric's sprite, see `Sprites::name_sprites`
+    #[serde(default)]
+    pub sprite: Option<String>
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assets::geometry::SpriteId;
+    use crate::game::physics::AlphanumericBody;
+    use super::*;
+
+    #[test]
+    fn snapshot_survives_json() {
+        let snapshot = WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            level: Some(LevelName::Workshop),
+            time_in_level: Duration::from_millis(1500),
+            bodies: vec![BodySnapshot {
+                id: u128::MAX,
+                body_type: BodyType::Alphanumeric(AlphanumericBody { sprite: SpriteId(3), alphanumeric: 'a' }),
+                material: Material::Wood,
+                width: 20.0,
+                height: 18.0,
+                motion: BodyMotion::Dynamic,
+                enabled: true,
+                position: (10.0, 20.0),
+                angle: 0.5,
+                linear_velocity: (1.0, -2.0),
+                angular_velocity: 0.25,
+                triangles: vec![([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)], (255, 0, 0))],
+                sprite: Some("a_3".to_string())
+            }],
+            links: vec![(1, 2)]
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: WorldSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(restored.bodies[0].id, u128::MAX);
+    }
+
+    #[test]
+    fn snapshots_from_another_version_are_discarded() {
+        let path = std::env::temp_dir().join(format!("keyboard-zoo-snapshot-{}.json", std::process::id()));
+        let old = WorldSnapshot { version: SNAPSHOT_VERSION + 1, ..WorldSnapshot::default() };
+        old.save(&path).unwrap();
+        assert!(WorldSnapshot::load(&path).is_err());
+        WorldSnapshot { version: SNAPSHOT_VERSION, ..old }.save(&path).unwrap();
+        assert!(WorldSnapshot::load(&path).is_ok());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,16 +1,22 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::sync::mpsc::{channel, Receiver, Sender, SendError};
 use std::time::Duration;
+use sdl2::rect::{Point, Rect};
 use crate::characters::CharacterType;
+use crate::characters::interaction::InteractionMatrix;
 use crate::assets::geometry::SpriteAsset;
 use crate::config::PhysicsConfig;
 use crate::frame_rate::FrameRate;
-use crate::game::action::Direction;
+use crate::game::action::{Direction, ExplosionTarget};
 use crate::game::default::DefaultGame;
 use crate::game::event::GameEvent;
 use crate::game::Game;
 use crate::game::physics::Body;
+use crate::game::query::{BodyFilter, RayHit};
 use crate::game::scale::PhysicsScale;
+use crate::game::snapshot::WorldSnapshot;
 
 const UPDATE_FREQ: f64 = 1.0 / 60.0;
 
@@ -18,33 +24,51 @@ const UPDATE_FREQ: f64 = 1.0 / 60.0;
 enum GameSyncCommand {
     Quit,
     Push(Direction),
-    Explosion,
-    SpawnAsset(SpriteAsset),
+    Explosion(ExplosionTarget),
+    SpawnAsset(SpriteAsset, bool),
     SpawnCharacter(CharacterType),
     Destroy(u128),
+    BodyAt(Point, Sender<Option<Body>>),
+    BodiesInRect(Rect, Sender<Vec<Body>>),
+    RayCast(Point, Point, Sender<Option<RayHit>>),
+    Nearest(Point, BodyFilter, Sender<Option<Body>>),
+    Snapshot(Sender<WorldSnapshot>),
+    Restore(WorldSnapshot),
+    Resize(PhysicsScale),
 }
 
-#[derive(Debug, Clone)]
-enum GameSyncEvent {
-    GameEvent(GameEvent),
-    Bodies(Vec<Body>)
+/// Double buffered bodies shared with the game thread. Readers get a reference to the latest bodies,
+/// the game thread gets the previous bodies back to refill once nobody is reading them.
+#[derive(Clone, Default)]
+struct SharedBodies(Arc<Mutex<Arc<Vec<Body>>>>);
+
+impl SharedBodies {
+    fn read(&self) -> Arc<Vec<Body>> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn publish(&self, bodies: Vec<Body>) -> Option<Vec<Body>> {
+        let previous = mem::replace(&mut *self.0.lock().unwrap(), Arc::new(bodies));
+        Arc::try_unwrap(previous).ok()
+    }
 }
 
 pub struct AsyncGame {
-    event_rx: Receiver<GameSyncEvent>,
+    event_rx: Receiver<GameEvent>,
     command_tx: Sender<GameSyncCommand>,
-    latest_bodies: Vec<Body>
+    bodies: SharedBodies
 }
 
 impl AsyncGame {
-    pub fn new(scale: PhysicsScale, physics_config: PhysicsConfig) -> Self {
+    pub fn new(scale: PhysicsScale, physics_config: PhysicsConfig, interactions: InteractionMatrix) -> Self {
         let (event_tx, event_rx) = channel();
         let (command_tx, command_rx) = channel();
-        let sync = AsyncGame { event_rx, command_tx, latest_bodies: vec![] };
+        let bodies = SharedBodies::default();
+        let sync = AsyncGame { event_rx, command_tx, bodies: bodies.clone() };
 
         thread::spawn(move|| {
-            let game = DefaultGame::new(scale, physics_config, None);
-            let mut thread = GameThread { event_tx, command_rx, game };
+            let game = DefaultGame::new(scale, physics_config, interactions, None);
+            let mut thread = GameThread { event_tx, command_rx, game, bodies, spare_bodies: vec![] };
             let mut frame_rate = FrameRate::new();
             let update_freq = Duration::from_secs_f64(UPDATE_FREQ);
             loop {
@@ -59,6 +83,13 @@ impl AsyncGame {
         });
         sync
     }
+
+    /// blocks until the game thread answers the query, none if the game thread has gone away
+    fn query<T, F: FnOnce(Sender<T>) -> GameSyncCommand>(&self, command: F) -> Option<T> {
+        let (tx, rx) = channel();
+        self.command_tx.send(command(tx)).ok()?;
+        rx.recv().ok()
+    }
 }
 
 impl Game for AsyncGame {
@@ -66,8 +97,8 @@ impl Game for AsyncGame {
         self.command_tx.send(GameSyncCommand::Push(direction)).unwrap();
     }
 
-    fn spawn_asset(&mut self, sprite: SpriteAsset) {
-        self.command_tx.send(GameSyncCommand::SpawnAsset(sprite)).unwrap();
+    fn spawn_asset(&mut self, sprite: SpriteAsset, typed: bool) {
+        self.command_tx.send(GameSyncCommand::SpawnAsset(sprite, typed)).unwrap();
     }
 
     fn spawn_character(&mut self, character: CharacterType) {
@@ -78,24 +109,48 @@ impl Game for AsyncGame {
         self.command_tx.send(GameSyncCommand::Destroy(id)).unwrap();
     }
 
-    fn explosion(&mut self) {
-        self.command_tx.send(GameSyncCommand::Explosion).unwrap();
+    fn explosion(&mut self, target: ExplosionTarget) {
+        self.command_tx.send(GameSyncCommand::Explosion(target)).unwrap();
     }
 
     fn update(&mut self, delta: Duration) -> Vec<GameEvent> {
         let mut buffer = vec![];
-        while let Ok(sync_event) = self.event_rx.try_recv() {
-            match sync_event {
-                GameSyncEvent::GameEvent(event) => buffer.push(event),
-                GameSyncEvent::Bodies(bodies) => self.latest_bodies = bodies,
-            }
+        while let Ok(event) = self.event_rx.try_recv() {
+            buffer.push(event);
         }
         buffer
     }
 
-    fn bodies(&self) -> Vec<Body> {
-        // TODO move bodies out of events so this call is not dependent on consuming events
-        self.latest_bodies.clone()
+    fn bodies(&self) -> Arc<Vec<Body>> {
+        self.bodies.read()
+    }
+
+    fn body_at(&self, point: Point) -> Option<Body> {
+        self.query(|tx| GameSyncCommand::BodyAt(point, tx)).flatten()
+    }
+
+    fn bodies_in_rect(&self, rect: Rect) -> Vec<Body> {
+        self.query(|tx| GameSyncCommand::BodiesInRect(rect, tx)).unwrap_or_default()
+    }
+
+    fn ray_cast(&self, from: Point, to: Point) -> Option<RayHit> {
+        self.query(|tx| GameSyncCommand::RayCast(from, to, tx)).flatten()
+    }
+
+    fn nearest(&self, point: Point, filter: BodyFilter) -> Option<Body> {
+        self.query(|tx| GameSyncCommand::Nearest(point, filter, tx)).flatten()
+    }
+
+    fn snapshot(&self) -> WorldSnapshot {
+        self.query(GameSyncCommand::Snapshot).unwrap_or_default()
+    }
+
+    fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.command_tx.send(GameSyncCommand::Restore(snapshot)).unwrap();
+    }
+
+    fn resize(&mut self, scale: PhysicsScale) {
+        self.command_tx.send(GameSyncCommand::Resize(scale)).unwrap();
     }
 
     fn debug_draw(&self) {
@@ -104,9 +159,11 @@ impl Game for AsyncGame {
 }
 
 struct GameThread {
-    event_tx: Sender<GameSyncEvent>,
+    event_tx: Sender<GameEvent>,
     command_rx: Receiver<GameSyncCommand>,
     game: DefaultGame,
+    bodies: SharedBodies,
+    spare_bodies: Vec<Body>
 }
 
 impl GameThread {
@@ -115,18 +172,36 @@ impl GameThread {
             match command {
                 GameSyncCommand::Quit => return Err("received quit command".to_string()),
                 GameSyncCommand::Push(direction) => self.game.push(direction),
-                GameSyncCommand::SpawnAsset(asset) => self.game.spawn_asset(asset),
+                GameSyncCommand::SpawnAsset(asset, typed) => self.game.spawn_asset(asset, typed),
                 GameSyncCommand::Destroy(id) => self.game.destroy(id),
                 GameSyncCommand::SpawnCharacter(character) => self.game.spawn_character(character),
-                GameSyncCommand::Explosion => self.game.explosion(),
+                GameSyncCommand::Explosion(target) => self.game.explosion(target),
+                GameSyncCommand::BodyAt(point, tx) => {
+                    let _ = tx.send(self.game.body_at(point));
+                }
+                GameSyncCommand::BodiesInRect(rect, tx) => {
+                    let _ = tx.send(self.game.bodies_in_rect(rect));
+                }
+                GameSyncCommand::RayCast(from, to, tx) => {
+                    let _ = tx.send(self.game.ray_cast(from, to));
+                }
+                GameSyncCommand::Nearest(point, filter, tx) => {
+                    let _ = tx.send(self.game.nearest(point, filter));
+                }
+                GameSyncCommand::Snapshot(tx) => {
+                    let _ = tx.send(self.game.snapshot());
+                }
+                GameSyncCommand::Restore(snapshot) => self.game.restore(snapshot),
+                GameSyncCommand::Resize(scale) => self.game.resize(scale),
             }
         }
         for event in self.game.update(delta).into_iter() {
-            self.event_tx.send(GameSyncEvent::GameEvent(event))
-                .map_err(|e| e.to_string())?;
+            self.event_tx.send(event).map_err(|e| e.to_string())?;
         }
-        self.event_tx.send(GameSyncEvent::Bodies(self.game.bodies()))
-            .map_err(|e| e.to_string())?;
+
+        let mut bodies = mem::take(&mut self.spare_bodies);
+        self.game.bodies_into(&mut bodies);
+        self.spare_bodies = self.bodies.publish(bodies).unwrap_or_default();
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
 use std::collections::HashMap;
 use std::time::Duration;
 use crate::characters::CharacterType;
@@ -7,7 +8,8 @@ use crate::config::{InputConfig, PlayerInputConfig};
 
 const AUTO_REPEAT_DELAY: Duration = Duration::from_millis(300);
 const AUTO_REPEAT_ITERATION: Duration = Duration::from_millis(25);
-const SANDBOX_QUIT_DELAY: Duration = Duration::from_millis(2000);
+/// how long quit & toggle fullscreen are held before they work in the sandbox
+const SANDBOX_HOLD_DELAY: Duration = Duration::from_millis(2000);
 
 #[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameInputKey {
@@ -22,6 +24,16 @@ pub enum GameInputKey {
     Nuke,
     Quit,
     Explosion,
+    ExplosionAt { x: i32, y: i32 },
+    ToggleFullscreen,
+    Resized { width: u32, height: u32 },
+}
+
+impl GameInputKey {
+    /// keys a toddler could use to escape the game
+    fn is_held_in_sandbox(&self) -> bool {
+        matches!(self, GameInputKey::Quit | GameInputKey::ToggleFullscreen)
+    }
 }
 
 #[derive(Hash, Clone, Debug, PartialEq, Eq)]
@@ -44,11 +56,14 @@ impl GameInput {
 enum KeyState {
     Down(GameInputKey),
     Up(GameInputKey),
+    /// a one-off input that cannot be held e.g. a mouse click
+    Pressed(GameInputKey),
 }
 
 pub struct GameInputContext {
     current: HashMap<GameInputKey, GameInput>,
     input_map: HashMap<Keycode, GameInputKey>,
+    explosion_button: MouseButton,
     sandbox_mode: bool,
 }
 
@@ -57,6 +72,7 @@ impl GameInputContext {
         Self {
             current: HashMap::new(),
             sandbox_mode: config.run_toddler_sandbox,
+            explosion_button: config.explosion_button,
             input_map: Self::input_map(config),
         }
     }
@@ -78,21 +94,31 @@ impl GameInputContext {
                     KeyState::Down(key) => {
                         let event = GameInput::new(key);
                         self.current.insert(key, event);
-                        if key != GameInputKey::Quit || !self.sandbox_mode {
+                        if !key.is_held_in_sandbox() || !self.sandbox_mode {
                             result.push(key);
                         }
                     }
                     KeyState::Up(key) => {
                         self.current.remove(&key);
                     }
+                    KeyState::Pressed(key) => {
+                        result.push(key);
+                    }
                 }
             }
         }
 
         // check for any held keys that have triggered a repeat
+        let sandbox_mode = self.sandbox_mode;
         for event in self.current.values_mut() {
-            if event.key == GameInputKey::Quit && event.duration >= SANDBOX_QUIT_DELAY {
+            if event.key == GameInputKey::Quit && event.duration >= SANDBOX_HOLD_DELAY {
                 result.push(GameInputKey::Quit);
+            } else if event.key == GameInputKey::ToggleFullscreen && sandbox_mode {
+                // toggled once per hold
+                if !event.repeating && event.duration >= SANDBOX_HOLD_DELAY {
+                    event.repeating = true;
+                    result.push(event.key);
+                }
             } else if matches!(event.key, GameInputKey::Up | GameInputKey::Down | GameInputKey::Left | GameInputKey::Right) {
                 // check auto-repeat
                 if event.repeating {
@@ -126,6 +152,10 @@ impl GameInputContext {
                 repeat: false,
                 ..
             } => self.input_map.get(&keycode).map(|&k| KeyState::Up(k)),
+            Event::MouseButtonDown { mouse_btn, x, y, .. } if mouse_btn == self.explosion_button =>
+                Some(KeyState::Pressed(GameInputKey::ExplosionAt { x, y })),
+            Event::Window { win_event: WindowEvent::SizeChanged(width, height), .. } =>
+                Some(KeyState::Pressed(GameInputKey::Resized { width: width as u32, height: height as u32 })),
             _ => None,
         }
     }
@@ -134,6 +164,7 @@ impl GameInputContext {
         let mut map = if config.baby_smash_mode {
             HashMap::from([
                 (config.quit, GameInputKey::Quit),
+                (config.toggle_fullscreen, GameInputKey::ToggleFullscreen),
                 (Keycode::A, GameInputKey::SpawnAsset('A')),
                 (Keycode::B, GameInputKey::SpawnAsset('B')),
                 (Keycode::C, GameInputKey::SpawnAsset('C')),
@@ -174,6 +205,7 @@ impl GameInputContext {
         } else {
             HashMap::from([
                 (config.quit, GameInputKey::Quit),
+                (config.toggle_fullscreen, GameInputKey::ToggleFullscreen),
             ])
         };
 
@@ -196,3 +228,29 @@ impl GameInputContext {
         map.insert(config.explosion, GameInputKey::Explosion);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sdl2::keyboard::Mod;
+    use crate::config::Config;
+    use super::*;
+
+    #[test]
+    fn sandbox_only_toggles_fullscreen_when_held() {
+        let mut config = Config::default().input;
+        config.run_toddler_sandbox = true;
+        let mut inputs = GameInputContext::new(config);
+        let press = Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(config.toggle_fullscreen),
+            scancode: None,
+            keymod: Mod::empty(),
+            repeat: false
+        };
+        assert!(inputs.update(Duration::ZERO, [press].into_iter()).is_empty());
+        assert!(inputs.update(Duration::from_millis(500), std::iter::empty()).is_empty());
+        assert_eq!(inputs.update(SANDBOX_HOLD_DELAY, std::iter::empty()), vec![GameInputKey::ToggleFullscreen]);
+        assert!(inputs.update(SANDBOX_HOLD_DELAY, std::iter::empty()).is_empty());
+    }
+}
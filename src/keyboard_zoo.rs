@@ -3,7 +3,7 @@ use std::rc::Rc;
 use std::time::Duration;
 use rand::{Rng, thread_rng};
 use crate::build_info::nice_app_name;
-use crate::config::{Config, VideoMode};
+use crate::config::{Config, DisplaySelection, VideoMode};
 use crate::frame_rate::FrameRate;
 use crate::icon::app_icon;
 use sdl2::image::{InitFlag as ImageInitFlag, Sdl2ImageContext};
@@ -11,19 +11,26 @@ use sdl2::mixer::{InitFlag as MixerInitFlag, DEFAULT_CHANNELS, DEFAULT_FORMAT};
 use sdl2::pixels::Color;
 use sdl2::render::{TextureCreator, WindowCanvas};
 use sdl2::ttf::Sdl2TtfContext;
-use sdl2::{AudioSubsystem, EventPump, Sdl};
-use sdl2::rect::Rect;
-use sdl2::video::WindowContext;
+use sdl2::{AudioSubsystem, EventPump, Sdl, VideoSubsystem};
+use sdl2::rect::{Point, Rect};
+use sdl2::video::{FullscreenType, Window, WindowContext};
 use crate::animate::Animations;
 use crate::animate::event::AnimationEvent;
+use crate::characters::interaction::InteractionMatrix;
+use crate::characters::definition::load_definitions;
+use crate::assets::levels;
+use crate::assets::levels::load_levels;
+use crate::characters::registry::{self, CharacterWeights};
 use crate::characters::render::CharacterRender;
+use crate::characters::lifetime::CharacterState;
 use crate::assets::sound::Sound;
 use crate::assets::sprites::Sprites;
-use crate::game::action::Direction;
+use crate::game::action::{Direction, ExplosionTarget};
 use crate::game::event::GameEvent;
-use crate::game::{Game, game};
+use crate::game::{Game, game, material};
 use crate::game::physics::{Body, BodyType};
 use crate::game::scale::PhysicsScale;
+use crate::game::snapshot::WorldSnapshot;
 use crate::game_input::{GameInputContext, GameInputKey};
 use crate::{characters, particles};
 use crate::particles::Particles;
@@ -33,6 +40,8 @@ use crate::particles::source::ParticleSource;
 
 const MAX_FOREGROUND_PARTICLES: usize = 200000;
 const MAX_BACKGROUND_PARTICLES: usize = 200000;
+const CHAIN_COLOR: Color = Color::RGB(0xDD, 0xDD, 0xDD);
+const STUN_COLOR: Color = Color::RGB(0xFF, 0x40, 0x40);
 
 pub struct KeyboardZoo {
     config: Config,
@@ -48,6 +57,9 @@ pub struct KeyboardZoo {
 
 impl KeyboardZoo {
     pub fn new() -> Result<Self, String> {
+        // the config may refer to characters from definition files & levels from level files
+        registry::register(load_definitions(&Config::characters_path()?)?)?;
+        levels::register(load_levels(&Config::levels_path()?)?)?;
         let config = Config::load()?;
         let sdl = sdl2::init()?;
         let image = sdl2::image::init(ImageInitFlag::PNG)?;
@@ -58,9 +70,16 @@ impl KeyboardZoo {
             video.disable_screen_saver();
         }
 
+        let display = Self::display_bounds(&video, &config.video.display)?;
+        let mut all_displays = display;
+        for index in 0..video.num_video_displays()? {
+            all_displays = all_displays.union(video.display_bounds(index)?);
+        }
+
         let (width, height) = match config.video.mode {
             VideoMode::Window { width, height } => (width, height),
             VideoMode::FullScreen { width, height } => (width, height),
+            VideoMode::SpanDisplays => all_displays.size(),
             _ => (1, 1),
         };
 
@@ -72,11 +91,26 @@ impl KeyboardZoo {
             VideoMode::FullScreenDesktop => {
                 window_builder.fullscreen_desktop();
             }
-            _ => {}
+            VideoMode::SpanDisplays => {
+                window_builder.borderless();
+            }
+            _ => {
+                window_builder.resizable();
+            }
+        };
+
+        // fullscreen modes take over whichever display the window starts on
+        let (x, y) = match config.video.mode {
+            VideoMode::SpanDisplays => (all_displays.x(), all_displays.y()),
+            _ => (
+                display.x() + (display.width() as i32 - width as i32) / 2,
+                display.y() + (display.height() as i32 - height as i32) / 2
+            )
         };
 
         let mut window = window_builder
-            .position_centered()
+            .position(x, y)
+            .allow_highdpi()
             .opengl()
             .build()
             .map_err(|e| e.to_string())?;
@@ -102,6 +136,7 @@ impl KeyboardZoo {
         sdl2::mixer::Music::set_volume(config.audio.music_volume());
 
         let texture_creator = canvas.texture_creator();
+        let (drawable_width, drawable_height, pixel_ratio) = Self::drawable_size(canvas.window());
 
         Ok(Self {
             config,
@@ -112,7 +147,8 @@ impl KeyboardZoo {
             texture_creator,
             event_pump,
             _audio: audio,
-            particle_scale: particles::scale::Scale::new((width, height)),
+            particle_scale: particles::scale::Scale::new((drawable_width, drawable_height))
+                .with_pixel_ratio(pixel_ratio as f64),
         })
     }
 
@@ -133,17 +169,40 @@ impl KeyboardZoo {
             self.particle_scale
         )?;
 
-        let mut character_render = CharacterRender::new(&self.texture_creator)?;
+        let mut character_render = CharacterRender::new(&self.texture_creator);
         let mut sprites = Sprites::new(&self.texture_creator)?;
         let mut sound = Sound::new(self.config.audio)?;
         let mut character_sound  = characters::sound(self.config.audio)?;
 
         let mut inputs = GameInputContext::new(self.config.input);
-        let (width, height) = self.canvas.borrow().window().size();
-        let scale = PhysicsScale::new(width, height, self.config.physics);
-        let mut game = game(scale, self.config.physics, self.canvas.clone());
+        // characters that can't be drawn are never spawned
+        let mut weights = self.config.characters.weights.clone();
+        weights.extend(registry::behaviours()
+            .map(|b| b.character_type())
+            .filter(|&character| !character_render.can_draw(character))
+            .map(|character| (character, 0.0)));
+        let character_weights = CharacterWeights::new(&weights);
+        let (mut width, mut height, pixel_ratio) = Self::drawable_size(self.canvas.borrow().window());
+        let mut scale = PhysicsScale::new(width, height, self.config.physics).with_pixel_ratio(pixel_ratio);
+        let interactions = InteractionMatrix::new(&self.config.characters.interactions);
+        let mut game = game(scale.clone(), self.config.physics, interactions, self.canvas.clone());
+        // without a session path the game is played without resuming or saving
+        let session_path = if self.config.physics.resume_session {
+            Config::session_path()
+                .map_err(|e| println!("Cannot find where to save the session, {}", e))
+                .ok()
+        } else {
+            None
+        };
+        if let Some(session_path) = &session_path {
+            // a missing or unreadable session just starts a new game
+            if let Ok(mut snapshot) = WorldSnapshot::load(session_path) {
+                sprites.find_sprites(&mut snapshot);
+                game.restore(snapshot);
+            }
+        }
 
-        let mut animations = Animations::new();
+        let mut animations = Animations::new(self.config.input.nuke_pattern);
 
         let mut frame_rate = FrameRate::new();
 
@@ -175,14 +234,40 @@ impl KeyboardZoo {
                     GameInputKey::Left => game.push(Direction::Left),
                     GameInputKey::Right => game.push(Direction::Right),
                     GameInputKey::SpawnAsset(ch) => {
-                        sprites.pick_sprite_by_char(ch).map(|sprite| game.spawn_asset(sprite));
+                        sprites.pick_sprite_by_char(ch).map(|sprite| game.spawn_asset(sprite, true));
                     },
-                    GameInputKey::SpawnRandomAsset => game.spawn_asset(sprites.pick_random_sprite()),
-                    GameInputKey::SpawnCharacter(character) => game.spawn_character(character),
-                    GameInputKey::SpawnRandomCharacter => game.spawn_character(rng.gen()),
-                    GameInputKey::Nuke => animations.nuke(game.bodies().into_iter().map(|b| b.id).collect()),
-                    GameInputKey::Explosion => game.explosion(),
+                    GameInputKey::SpawnRandomAsset => game.spawn_asset(sprites.pick_random_sprite(), false),
+                    GameInputKey::SpawnCharacter(character) => if character_render.can_draw(character) {
+                        game.spawn_character(character)
+                    }
+                    GameInputKey::SpawnRandomCharacter => game.spawn_character(rng.sample(&character_weights)),
+                    GameInputKey::Nuke => animations.nuke(
+                        game.bodies().iter()
+                            .filter(|b| !matches!(b.body_type, BodyType::Prop))
+                            .map(|b| (b.id, b.aabb.center()))
+                            .collect(),
+                        Point::new(width as i32 / 2, height as i32 / 2)
+                    ),
+                    GameInputKey::Explosion => game.explosion(self.config.input.explosion_target),
+                    GameInputKey::ExplosionAt { x, y } => {
+                        let point = scale.window_point_to_sdl(x, y);
+                        game.explosion(ExplosionTarget::Point { x: point.x(), y: point.y() })
+                    }
                     GameInputKey::Quit => break 'game,
+                    GameInputKey::ToggleFullscreen => self.toggle_fullscreen()?,
+                    GameInputKey::Resized { .. } => {
+                        // the event size is in logical points & can lag behind the window, so ask the window
+                        let pixel_ratio;
+                        (width, height, pixel_ratio) = Self::drawable_size(self.canvas.borrow().window());
+                        scale = scale.resized(width, height, pixel_ratio);
+                        game.resize(scale.clone());
+                        self.particle_scale = particles::scale::Scale::new((width, height))
+                            .with_pixel_ratio(pixel_ratio as f64);
+                        fg_particles.set_scale(self.particle_scale);
+                        bg_particles.set_scale(self.particle_scale);
+                        bg_particles.clear_sources();
+                        bg_particles.add_source(bg_source.build(&self.canvas.borrow(), &self.particle_scale));
+                    }
                 }
             }
 
@@ -199,10 +284,12 @@ impl KeyboardZoo {
                         match body.body_type {
                             BodyType::Alphanumeric(alphanumeric_body) => {
                                 sound.play_alphanumeric(alphanumeric_body.alphanumeric);
-                                fg_particles.add_source(sprite_lattice_source(body.polygons, &self.particle_scale));
+                                animations.spawned(body.id);
+                                fg_particles.add_source(sprite_lattice_source(body.polygons(), &self.particle_scale));
                             }
                             BodyType::Character(character_body) => {
                                 character_sound.play_create(character_body.character_type())?;
+                                animations.spawned(body.id);
                             }
                             _ => {}
                         }
@@ -210,31 +297,62 @@ impl KeyboardZoo {
                     GameEvent::Destroy(body) => {
                         match body.body_type {
                             BodyType::Alphanumeric(_) => {
-                                for triangle in body.polygons.into_iter() {
+                                for triangle in body.polygons().into_iter() {
                                     fg_particles.add_source(sprite_triangle_source(triangle, &self.particle_scale));
                                 }
                                 sound.play_destroy();
                             }
                             BodyType::Character(character_body) => {
                                 character_sound.play_destroy(character_body.character_type())?;
-                                // TODO particles?
+                                let effect = character_body.character_type().death_effect();
+                                if let Some(source) = particles::prescribed::character_death(effect, body.aabb, &self.particle_scale) {
+                                    fg_particles.add_source(source);
+                                }
                             }
                             _ => {}
                         }
 
                     }
+                    GameEvent::Fractured { .. } => {
+                        sound.play_destroy();
+                    }
                     GameEvent::CharacterAttack(character) => {
                         character_sound.play_attack(character)?;
                     }
-                    GameEvent::Explosion { x, y } => {
+                    GameEvent::CharacterEats { predator, .. } => {
+                        character_sound.play_eat(predator)?;
+                    }
+                    GameEvent::CharacterPoweredUp(character) => {
+                        character_sound.play_power_up(character)?;
+                    }
+                    GameEvent::CharacterMunch { point, color } => {
+                        fg_particles.add_source(particles::prescribed::munch(point, color, &self.particle_scale));
+                    }
+                    GameEvent::CharacterStateChanged { id, character, to, .. } => {
+                        character_sound.play_state(character, to)?;
+                        if to == CharacterState::Stunned {
+                            animations.flash(id, STUN_COLOR);
+                        }
+                    }
+                    GameEvent::Explosion { x, y, radius, strength } => {
                         fg_particles.add_source(
-                            particles::prescribed::explosion((x, y), &self.particle_scale)
+                            particles::prescribed::explosion((x, y), radius, strength, &self.particle_scale)
                         );
                         sound.play_explosion();
                     }
-                    GameEvent::HeavyCollision => {
-                        sound.play_collision();
+                    GameEvent::Collision(collision) => {
+                        // characters make their own noises
+                        if !collision.involves_character() {
+                            let intensity = collision.impulse / self.config.physics.collision_events.loud_impulse;
+                            sound.play_collision(
+                                material::collision_sound(collision.body.material),
+                                collision.point.x() as f32 / width as f32,
+                                intensity
+                            );
+                            fg_particles.add_source(particles::prescribed::dust(collision.point, intensity, &self.particle_scale));
+                        }
                     }
+                    GameEvent::WorldSettled | GameEvent::WorldWoke => {}
                 }
             }
 
@@ -250,7 +368,7 @@ impl KeyboardZoo {
                 // draw bg particles
                 bg_particles.draw(&mut self.canvas.borrow_mut())?;
 
-                self.draw_bodies(&mut sprites, &mut character_render, game.bodies())?;
+                self.draw_bodies(&mut sprites, &mut character_render, &animations, &game.bodies())?;
 
                 // draw fg particles
                 fg_particles.draw(&mut self.canvas.borrow_mut())?;
@@ -260,18 +378,52 @@ impl KeyboardZoo {
         }
 
         sound.halt_music();
+        if let Some(session_path) = session_path {
+            let mut snapshot = game.snapshot();
+            sprites.name_sprites(&mut snapshot);
+            if let Err(e) = snapshot.save(&session_path) {
+                println!("Cannot save the session to {}, {}", session_path.display(), e);
+            }
+        }
         Ok(())
     }
 
-    fn draw_bodies(&self, sprites: &mut Sprites, character_render: &mut CharacterRender, bodies: Vec<Body>) -> Result<(), String> {
+    fn draw_bodies(&self, sprites: &mut Sprites, character_render: &mut CharacterRender, animations: &Animations, bodies: &[Body]) -> Result<(), String> {
         let mut canvas = self.canvas.borrow_mut();
-        for body in bodies.into_iter() {
-            match body.body_type {
+
+        // props are the scenery behind everything else
+        for body in bodies.iter().filter(|b| matches!(b.body_type, BodyType::Prop)) {
+            for triangle in body.polygons().iter() {
+                triangle.draw(&mut canvas, Point::new(0, 0), 255)?;
+            }
+        }
+
+        // word chain strings are drawn behind the bodies they link
+        canvas.set_draw_color(CHAIN_COLOR);
+        for joint in bodies.iter().flat_map(|b| b.joints.iter()) {
+            canvas.draw_line(joint.from, joint.to)?;
+        }
+
+        for body in bodies.iter() {
+            let look = animations.look(body.id);
+            match &body.body_type {
                 BodyType::Alphanumeric(asset_body) => {
-                    sprites.draw_sprite(&mut canvas, &asset_body.name, body.aabb, body.angle)?;
+                    sprites.draw_sprite(&mut canvas, asset_body.sprite, body.aabb, body.angle, look)?;
                 }
                 BodyType::Character(character) => {
-                    character_render.draw_character(&mut canvas, character, body.aabb, body.angle)?;
+                    character_render.draw_character(&mut canvas, character, body.aabb, body.angle, look)?;
+                }
+                // triangles are already where they're drawn so can only fade
+                BodyType::Fragment(fragment) => {
+                    let alpha = (fragment.alpha() as f32 * look.alpha).round() as u8;
+                    for triangle in body.polygons().iter() {
+                        triangle.draw(&mut canvas, Point::new(0, 0), alpha)?;
+                    }
+                }
+                BodyType::Segment(_) => {
+                    for triangle in body.polygons().iter() {
+                        triangle.draw(&mut canvas, Point::new(0, 0), look.alpha_mod())?;
+                    }
                 }
                 _ => {}
             }
@@ -280,6 +432,43 @@ impl KeyboardZoo {
         Ok(())
     }
 
+    /// physical pixels to draw into & how many there are per logical window point
+    fn drawable_size(window: &Window) -> (u32, u32, f32) {
+        let (width, height) = window.drawable_size();
+        let (logical_width, _) = window.size();
+        (width, height, width as f32 / logical_width.max(1) as f32)
+    }
+
+    /// bounds of the configured display, falling back to the primary display if it cannot be found
+    fn display_bounds(video: &VideoSubsystem, selection: &DisplaySelection) -> Result<Rect, String> {
+        let count = video.num_video_displays()?;
+        let index = match selection {
+            DisplaySelection::Primary => Some(0),
+            DisplaySelection::Index(index) => Some(*index).filter(|i| (0..count).contains(i)),
+            DisplaySelection::Name(name) => (0..count).find(|&i| video.display_name(i).map_or(false, |n| &n == name))
+        };
+        let index = index.unwrap_or_else(|| {
+            println!("display {:?} not found, using the primary display", selection);
+            0
+        });
+        video.display_bounds(index)
+    }
+
+    fn toggle_fullscreen(&self) -> Result<(), String> {
+        if self.config.video.mode == VideoMode::SpanDisplays {
+            // already covers every display
+            return Ok(());
+        }
+        let mut canvas = self.canvas.borrow_mut();
+        let window = canvas.window_mut();
+        let next = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off
+        };
+        // the window reports its new size with a resize event
+        window.set_fullscreen(next)
+    }
+
     fn clear(&self) {
         let mut canvas = self.canvas.borrow_mut();
         canvas.set_draw_color(Color::BLACK);
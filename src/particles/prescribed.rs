@@ -10,6 +10,7 @@ use sdl2::rect::{Point, Rect};
 use std::time::Duration;
 use sdl2::render::WindowCanvas;
 use strum_macros::EnumIter;
+use crate::characters::effect::DeathEffect;
 use crate::game::physics::Body;
 use crate::game::polygon::{Circle, Triangle};
 
@@ -42,7 +43,7 @@ impl PrescribedParticles {
     }
 
     pub fn build(&self, canvas: &WindowCanvas, scale: &Scale) -> Box<dyn ParticleSource> {
-        let (window_width, window_height) = canvas.window().size();
+        let (window_width, window_height) = canvas.window().drawable_size();
         let window = Rect::new(0, 0, window_width, window_height);
         match self {
             PrescribedParticles::Orbit => orbit(window, scale),
@@ -52,8 +53,13 @@ impl PrescribedParticles {
     }
 }
 
-pub fn explosion<P : Into<Point>>(center: P, scale: &Scale) -> Box<dyn ParticleSource> {
-    let source = scale.polygon_lattice_source(&[Circle::new(100, center)]);
+const EXPLOSION_RADIUS_RATIO: f64 = 0.1;
+
+/// radius is the area of effect of the explosion in pixels, strength is relative to a standard explosion
+pub fn explosion<P : Into<Point>>(center: P, radius: u32, strength: f32, scale: &Scale) -> Box<dyn ParticleSource> {
+    let strength = strength as f64;
+    let radius = (radius as f64 * EXPLOSION_RADIUS_RATIO).round() as u32;
+    let source = scale.polygon_lattice_source(&[Circle::new(radius.max(1), center)]);
     RandomParticleSource::new(source, ParticleModulation::Cascade)
         .with_properties(ProbabilityTable::identity(ParticleProperties::new(
             ParticleSprite::all_sprite_based().as_slice(),
@@ -61,11 +67,11 @@ pub fn explosion<P : Into<Point>>(center: P, scale: &Scale) -> Box<dyn ParticleS
                 ParticleColor::rgb(0.7, 0.4, 0.4),
                 ParticleColor::rgb(0.3, 0.3, 0.3),
             ),
-            1.5,
+            1.5 * strength,
             0.0,
         )))
-        .with_velocity((Vec2D::new(0.0, 0.0), Vec2D::new(0.25, 0.25)))
-        .with_fade_out((1.5, 0.5))
+        .with_velocity((Vec2D::new(0.0, 0.0), Vec2D::new(0.25, 0.25) * strength))
+        .with_fade_out((1.5 * strength, 0.5 * strength))
         .into_box()
 }
 
@@ -146,6 +152,67 @@ pub fn sprite_triangle_source(triangle: Triangle, scale: &Scale) -> Box<dyn Part
         .into_box()
 }
 
+/// a puff of dust where bodies collide, intensity is 0..1 relative to a loud collision
+pub fn dust(point: Point, intensity: f32, scale: &Scale) -> Box<dyn ParticleSource> {
+    let intensity = intensity.clamp(0.0, 1.0) as f64;
+    let count = 3 + (15.0 * intensity).round() as u32;
+    RandomParticleSource::new(scale.static_source(point), ParticleModulation::CascadeLimit { count })
+        .with_static_properties(
+            ParticleSprite::Circle05,
+            ParticleColor::rgb(0.6, 0.55, 0.5),
+            1.0,
+            0.0,
+        )
+        .with_fade_out((0.5 + 0.5 * intensity, 0.25))
+        .with_velocity((Vec2D::new(0.0, -0.1), Vec2D::new(0.15, 0.1) * (0.5 + intensity)))
+        .into_box()
+}
+
+/// particles where a character died, the aabb is the character on screen
+pub fn character_death(effect: DeathEffect, aabb: Rect, scale: &Scale) -> Option<Box<dyn ParticleSource>> {
+    let radius = (aabb.width().min(aabb.height()) / 2).max(1);
+    let source = match effect {
+        DeathEffect::Smoke => RandomParticleSource::new(scale.static_source(aabb.center()), ParticleModulation::CascadeLimit { count: 8 })
+            .with_properties(ProbabilityTable::identity(ParticleProperties::new(
+                &[ParticleSprite::Smoke01, ParticleSprite::Smoke02, ParticleSprite::Smoke03],
+                ParticleColor::rgb(0.5, 0.5, 0.5),
+                1.0,
+                (0.0, 30.0),
+            )))
+            .with_fade_out((1.0, 0.5))
+            .with_velocity((Vec2D::new(0.0, -0.1), Vec2D::new(0.05, 0.05))),
+        DeathEffect::Sparks { color: (r, g, b) } => RandomParticleSource::new(scale.static_source(aabb.center()), ParticleModulation::CascadeLimit { count: 20 })
+            .with_properties(ProbabilityTable::identity(ParticleProperties::new(
+                &[ParticleSprite::Spark01, ParticleSprite::Spark02, ParticleSprite::Spark03, ParticleSprite::Spark04],
+                ParticleColor::from_sdl(Color::RGB(r, g, b)),
+                0.5,
+                (0.0, 90.0),
+            )))
+            .with_fade_out((0.75, 0.25))
+            .with_velocity((Vec2D::ZERO, Vec2D::new(0.3, 0.3))),
+        DeathEffect::Pellets { color: (r, g, b) } => RandomParticleSource::new(
+            scale.polygon_lattice_source(&[Circle::new(radius, aabb.center())]),
+            ParticleModulation::Cascade
+        )
+            .with_static_properties(ParticleSprite::Circle05, ParticleColor::from_sdl(Color::RGB(r, g, b)), 0.5, 0.0)
+            .with_fade_out((1.5, 0.5))
+            .with_velocity((Vec2D::ZERO, Vec2D::new(0.1, 0.1)))
+            .with_acceleration(Vec2D::new(0.0, 1.0)), // gravity
+        DeathEffect::Vanish => return None
+    };
+    Some(source.into_box())
+}
+
+/// crumbs of a letter where a character took a bite out of it
+pub fn munch(point: Point, (r, g, b): (u8, u8, u8), scale: &Scale) -> Box<dyn ParticleSource> {
+    RandomParticleSource::new(scale.static_source(point), ParticleModulation::CascadeLimit { count: 8 })
+        .with_static_properties(ParticleSprite::Circle05, ParticleColor::from_sdl(Color::RGB(r, g, b)), 0.5, 0.0)
+        .with_fade_out((0.75, 0.25))
+        .with_velocity((Vec2D::new(0.0, -0.2), Vec2D::new(0.15, 0.1)))
+        .with_acceleration(Vec2D::new(0.0, 1.5)) // gravity
+        .into_box()
+}
+
 pub fn sprite_lattice_source(polygons: Vec<Triangle>, scale: &Scale) -> Box<dyn ParticleSource> {
     let position = scale.polygon_lattice_source(&polygons);
     RandomParticleSource::new(position, ParticleModulation::Cascade)
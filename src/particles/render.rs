@@ -42,6 +42,10 @@ impl<'a> ParticleRender<'a> {
         })
     }
 
+    pub fn set_scale(&mut self, scale: Scale) {
+        self.scale = scale;
+    }
+
     pub fn clear(&mut self) {
         self.particles.clear();
     }
@@ -72,7 +76,7 @@ impl<'a> ParticleRender<'a> {
             let point = self.scale.point_to_render_space(particle.position());
 
             if let Some(snip) = self.sprite_snips.get(&particle.sprite()) {
-                let scale = BASE_SCALE * particle.size();
+                let scale = BASE_SCALE * particle.size() * self.scale.pixel_ratio();
                 let rect = Rect::from_center(
                     point,
                     (scale * snip.width() as f64).round() as u32,
@@ -12,17 +12,29 @@ const PERIMETER_SCALE: usize = 2;
 pub struct Scale {
     window_width: f64,
     window_height: f64,
+    pixel_ratio: f64,
 }
 
 impl Scale {
+    /// window size is the drawable size in physical pixels
     pub fn new(window_size: (u32, u32)) -> Self {
         let (window_width, window_height) = window_size;
         Self {
             window_width: window_width as f64,
             window_height: window_height as f64,
+            pixel_ratio: 1.0,
         }
     }
 
+    /// physical pixels per logical point, so particle sprites are the same size on HiDPI displays
+    pub fn with_pixel_ratio(self, pixel_ratio: f64) -> Self {
+        Self { pixel_ratio, ..self }
+    }
+
+    pub fn pixel_ratio(&self) -> f64 {
+        self.pixel_ratio
+    }
+
     pub fn point_to_particle_space<P: Into<Point>>(&self, point: P) -> Vec2D {
         let point = point.into();
         Vec2D::new(
@@ -169,4 +181,11 @@ mod tests {
         let observed = scale.rect_to_particle_space(Rect::new(480, 540, 96, 27));
         assert_eq!(observed, RectF::new(0.25, 0.5, 0.05, 0.025))
     }
+
+    #[test]
+    fn pixel_ratio_does_not_change_particle_space() {
+        let scale = Scale::new((3840, 2160)).with_pixel_ratio(2.0);
+        let observed = scale.point_to_particle_space((960, 1080));
+        assert_eq!(observed, Vec2D::new(0.25, 0.5))
+    }
 }